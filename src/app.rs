@@ -1,8 +1,9 @@
 use leptos::prelude::*;
-use leptos_meta::{provide_meta_context, MetaTags, Stylesheet, Title};
+use leptos_meta::{provide_meta_context, Link, Meta, MetaTags, Stylesheet, Title};
 use leptos_router::{
-    components::{Route, Router, Routes, A,},
-    StaticSegment,
+    components::{Form, Route, Router, Routes, A,},
+    hooks::{use_params_map, use_query_map},
+    ParamSegment, SsrMode, StaticSegment,
 };
 use web_sys::WheelEvent;
 use web_sys::Touch;
@@ -14,13 +15,119 @@ use leptos::server::ServerAction;
 use leptos::ev;
 use leptos::task::spawn_local;
 use serde::{Deserialize, Serialize}; // Import Serialize here for client-side data structures
+use crate::csrf::CsrfClient;
+use crate::settings::{site_settings, ProfileLink, SiteSettings, SETTINGS_ELEMENT_ID};
+use crate::dates::{format_date, format_datetime_local, format_rfc3339, format_timestamp, parse_datetime_local};
+use time::OffsetDateTime;
 
 // --- Data Structures for Client-Side (MUST MATCH SERVER) ---
 // These need to be accessible on the client side for sending data
+
+// Only published posts and projects, and scheduled ones whose time has come, are public.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PublishStatus {
+    Draft,
+    // Records from before statuses existed have none, and were already public
+    #[default]
+    Published,
+    Scheduled,
+}
+
+impl PublishStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            PublishStatus::Draft => "Draft",
+            PublishStatus::Published => "Published",
+            PublishStatus::Scheduled => "Scheduled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Tag {
+    pub name: String,
+    pub slug: String,
+}
+
+// An uploaded image in the media library, see `crate::media`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MediaRecord {
+    pub id: String,
+    // Where the file is served, e.g. `/media/9f86d081884c7d659a2feaa0c55ad015.png`
+    pub url: String,
+    // The smallest resized copy, for the admin panel's library
+    pub thumbnail_url: String,
+    pub original_name: String,
+    pub content_type: String,
+    pub size: u64,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+// As stored in the `media` table (server only)
+#[cfg(feature = "ssr")]
+#[derive(Deserialize, Debug)]
+pub(crate) struct MediaFromDB {
+    pub(crate) id: surrealdb::sql::Thing,
+    pub(crate) file_name: String,
+    pub(crate) original_name: String,
+    pub(crate) content_type: String,
+    pub(crate) size: u64,
+    // Images uploaded before resizing was added have none of these
+    #[serde(default)]
+    pub(crate) width: Option<u32>,
+    #[serde(default)]
+    pub(crate) height: Option<u32>,
+    #[serde(default)]
+    pub(crate) variant_widths: Vec<u32>,
+    #[serde(default)]
+    pub(crate) webp: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) created_at: OffsetDateTime,
+}
+
+#[cfg(feature = "ssr")]
+impl From<MediaFromDB> for MediaRecord {
+    fn from(m: MediaFromDB) -> Self {
+        MediaRecord {
+            id: m.id.to_string(),
+            url: format!("/media/{}", m.file_name),
+            thumbnail_url: crate::media::ImageInfo::from(&m).thumbnail_url(),
+            original_name: m.original_name,
+            content_type: m.content_type,
+            size: m.size,
+            width: m.width,
+            height: m.height,
+            created_at: m.created_at,
+        }
+    }
+}
+
+// An image along with the resized copies made of it on upload, for `<Picture>`. Images that
+// weren't uploaded to the media library have no copies or dimensions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResponsiveImage {
+    pub src: String,
+    // `srcset` candidates in the uploaded format, and in WebP if that came out smaller
+    pub srcset: String,
+    pub webp_srcset: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlogPost {
     pub title: String,
     pub content: String,
+    pub status: PublishStatus,
+    // RFC 3339, required when `status` is `Scheduled`
+    pub publish_at: Option<String>,
+    // Left out of the request altogether when empty, since server functions send forms
+    #[serde(default)]
+    pub tags: Vec<String>,
     // Note: created_at will be set on the server, no need here for client-side input
 }
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -29,6 +136,26 @@ pub struct BlogRecord {
     pub id: String,
     pub title: String,
     pub content: String,
+    #[serde(default)]
+    pub slug: String,
+    #[serde(default)]
+    pub status: PublishStatus,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub publish_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    // Rendered from the Markdown in `content` by the server, on the post's own page only
+    #[serde(default)]
+    pub content_html: String,
+    #[serde(default)]
+    pub excerpt: String,
+    // The first image in `content`, for link previews
+    #[serde(default)]
+    pub cover_image: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +163,15 @@ pub struct Project {
     pub title: String,
     pub content: String,
     pub link: String,
+    pub description: String,
+    pub status: PublishStatus,
+    // RFC 3339, required when `status` is `Scheduled`
+    pub publish_at: Option<String>,
+    // Left out of the request altogether when empty, since server functions send forms
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // URL of the image shown on the project's card and page, usually from the media library
+    pub cover_image: Option<String>,
     // Note: created_at will be set on the server
 }
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -45,6 +181,376 @@ pub struct ProjectRecord {
     pub title: String,
     pub content: String,
     pub link: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub slug: String,
+    #[serde(default)]
+    pub status: PublishStatus,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub publish_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    // Rendered from the Markdown in `content` and `description` by the server, on the
+    // project's own page only
+    #[serde(default)]
+    pub content_html: String,
+    #[serde(default)]
+    pub description_html: String,
+    #[serde(default)]
+    pub excerpt: String,
+    #[serde(default)]
+    pub cover_image: Option<String>,
+    #[serde(default)]
+    pub cover: Option<ResponsiveImage>,
+    // The cover image, or else the first image in `content` or `description`, for link previews
+    #[serde(default)]
+    pub preview_image: Option<String>,
+}
+
+// --- Records as SurrealDB returns them (server only) ---
+#[cfg(feature = "ssr")]
+#[derive(Deserialize, Debug)]
+struct ProjectFromDB {
+    id: surrealdb::sql::Thing,
+    title: String,
+    content: String,
+    link: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    slug: String,
+    #[serde(default)]
+    status: PublishStatus,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    publish_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    updated_at: OffsetDateTime,
+    #[serde(default)]
+    tags: Vec<Tag>,
+    #[serde(default)]
+    cover_image: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+impl From<ProjectFromDB> for ProjectRecord {
+    fn from(p: ProjectFromDB) -> Self {
+        use crate::markdown::{excerpt, first_image};
+        use crate::media::responsive_image;
+
+        ProjectRecord {
+            id: p.id.to_string(), // The crucial conversion!
+            content_html: String::new(),
+            description_html: String::new(),
+            excerpt: excerpt(&p.content, 200),
+            preview_image: p
+                .cover_image
+                .clone()
+                .or_else(|| first_image(&p.content))
+                .or_else(|| first_image(&p.description)),
+            cover: p.cover_image.as_deref().map(responsive_image),
+            cover_image: p.cover_image,
+            title: p.title,
+            content: p.content,
+            link: p.link,
+            description: p.description,
+            slug: p.slug,
+            status: p.status,
+            publish_at: p.publish_at,
+            created_at: p.created_at,
+            updated_at: p.updated_at,
+            tags: p.tags,
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Deserialize, Debug)]
+struct BlogFromDB {
+    id: surrealdb::sql::Thing,
+    title: String,
+    content: String,
+    #[serde(default)]
+    slug: String,
+    #[serde(default)]
+    status: PublishStatus,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    publish_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    updated_at: OffsetDateTime,
+    #[serde(default)]
+    tags: Vec<Tag>,
+}
+
+#[cfg(feature = "ssr")]
+impl From<BlogFromDB> for BlogRecord {
+    fn from(p: BlogFromDB) -> Self {
+        use crate::markdown::{excerpt, first_image};
+
+        BlogRecord {
+            id: p.id.to_string(), // The crucial conversion!
+            content_html: String::new(),
+            excerpt: excerpt(&p.content, 200),
+            cover_image: first_image(&p.content),
+            title: p.title,
+            content: p.content,
+            slug: p.slug,
+            status: p.status,
+            publish_at: p.publish_at,
+            created_at: p.created_at,
+            updated_at: p.updated_at,
+            tags: p.tags,
+        }
+    }
+}
+
+// Records come back from the database with their Markdown source but no HTML. Rendering it
+// (highlighting and sanitising included) is kept to the pages that show one record in full,
+// and public listings drop the source, which they don't show, so it isn't sent at all.
+#[cfg(feature = "ssr")]
+trait RenderedRecord {
+    // Renders the Markdown for a record's own page.
+    fn rendered(self) -> Self;
+    // Drops what a public listing doesn't show; the excerpt and preview image stay.
+    fn for_listing(self) -> Self;
+}
+
+#[cfg(feature = "ssr")]
+impl RenderedRecord for ProjectRecord {
+    fn rendered(mut self) -> Self {
+        use crate::markdown::render_markdown;
+        self.content_html = render_markdown(&self.content);
+        self.description_html = render_markdown(&self.description);
+        self
+    }
+
+    fn for_listing(mut self) -> Self {
+        self.content = String::new();
+        self.description = String::new();
+        self
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl RenderedRecord for BlogRecord {
+    fn rendered(mut self) -> Self {
+        self.content_html = crate::markdown::render_markdown(&self.content);
+        self
+    }
+
+    fn for_listing(mut self) -> Self {
+        self.content = String::new();
+        self
+    }
+}
+
+// Every field of a post or project, plus its tags (see `crate::tags`).
+#[cfg(feature = "ssr")]
+const RECORD_FIELDS: &str = "*, ->tagged->tag.{ name, slug } AS tags";
+
+// The `WHERE` condition for what anonymous visitors may see, see `PublishStatus`.
+#[cfg(feature = "ssr")]
+pub const PUBLICLY_VISIBLE: &str =
+    "(status IS NONE OR status = 'published' OR (status = 'scheduled' AND publish_at <= time::now()))";
+
+// Checks the status and publish time sent by the admin panel, parsing the time for SurrealDB.
+#[cfg(feature = "ssr")]
+fn validate_publish_at(
+    status: PublishStatus,
+    publish_at: Option<&str>,
+) -> Result<Option<surrealdb::sql::Datetime>, String> {
+    let publish_at = publish_at
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            surrealdb::sql::Datetime::try_from(value)
+                .map_err(|_| format!("Invalid publish time: {}", value))
+        })
+        .transpose()?;
+    if status == PublishStatus::Scheduled && publish_at.is_none() {
+        return Err("Scheduled content needs a publish time".to_string());
+    }
+    Ok(publish_at)
+}
+
+// --- Pagination ---
+// Listings run newest first by `created_at`, with the record id breaking ties. A cursor names
+// the record a page continues from, as `<created_at in unix nanoseconds>-<record key>`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    // Pass back as `older` for the next page, and as `newer` for the one before
+    pub older: Option<String>,
+    pub newer: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+const MAX_PAGE_SIZE: u32 = 50;
+
+#[cfg(feature = "ssr")]
+fn page_cursor(id: &str, created_at: OffsetDateTime) -> String {
+    let key = id.split_once(':').map_or(id, |(_, key)| key);
+    format!("{}-{}", created_at.unix_timestamp_nanos(), key)
+}
+
+#[cfg(feature = "ssr")]
+fn parse_page_cursor(table: &str, cursor: &str) -> Option<(surrealdb::sql::Datetime, surrealdb::RecordId)> {
+    let (nanos, key) = cursor.split_once('-')?;
+    let created_at = OffsetDateTime::from_unix_timestamp_nanos(nanos.parse().ok()?).ok()?;
+    let created_at = surrealdb::sql::Datetime::try_from(format_rfc3339(created_at).as_str()).ok()?;
+    Some((created_at, record_id(table, key)))
+}
+
+// One page of the publicly visible records in `table`, after `older` or before `newer`,
+// and only those tagged with `tag` if one is given.
+#[cfg(feature = "ssr")]
+async fn fetch_page<FromDB, R>(
+    table: &str,
+    tag: Option<&str>,
+    older: Option<String>,
+    newer: Option<String>,
+    limit: u32,
+    cursor_of: fn(&R) -> String,
+) -> Result<Page<R>, ServerFnError>
+where
+    FromDB: serde::de::DeserializeOwned,
+    R: From<FromDB> + RenderedRecord,
+{
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+
+    let limit = limit.clamp(1, MAX_PAGE_SIZE) as usize;
+    // Going to newer records walks the listing backwards, then flips the page round
+    let backwards = newer.is_some();
+    let (cursor, condition, order) = match (newer, older) {
+        (Some(cursor), _) => (Some(cursor), "AND (created_at > $at OR (created_at = $at AND id > $id))", "ASC"),
+        (None, Some(cursor)) => (Some(cursor), "AND (created_at < $at OR (created_at = $at AND id < $id))", "DESC"),
+        (None, None) => (None, "", "DESC"),
+    };
+    let cursor = cursor
+        .map(|cursor| {
+            parse_page_cursor(table, &cursor)
+                .ok_or_else(||->ServerFnError{ ServerFnError::ServerError(format!("Invalid page cursor: {}", cursor))})
+        })
+        .transpose()?;
+    let from_cursor = cursor.is_some();
+    let (at, id) = cursor.unzip();
+    let tag_condition = if tag.is_some() { "AND $tag IN ->tagged->tag" } else { "" };
+
+    // One extra row tells whether there is another page after this one
+    let mut response = db
+        .query(format!(
+            "SELECT {RECORD_FIELDS} FROM type::table($table) WHERE {PUBLICLY_VISIBLE} {tag_condition} {condition} \
+             ORDER BY created_at {order}, id {order} LIMIT $limit"
+        ))
+        .bind(("table", table.to_string()))
+        .bind(("at", at))
+        .bind(("id", id))
+        .bind(("tag", tag.map(|slug| surrealdb::RecordId::from(("tag", slug)))))
+        .bind(("limit", limit + 1))
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let mut rows: Vec<FromDB> = response
+        .take(0)
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+
+    let more = rows.len() > limit;
+    rows.truncate(limit);
+    if backwards {
+        rows.reverse();
+    }
+    let items: Vec<R> = rows.into_iter().map(|row| R::from(row).for_listing()).collect();
+
+    // The cursor itself means there is a page on the side we came from
+    let (has_older, has_newer) = if backwards { (from_cursor, more) } else { (more, from_cursor) };
+    Ok(Page {
+        older: items.last().filter(|_| has_older).map(cursor_of),
+        newer: items.first().filter(|_| has_newer).map(cursor_of),
+        items,
+    })
+}
+
+#[leptos::server(GetProjectsPage, "/api")]
+pub async fn get_projects_page(
+    older: Option<String>,
+    newer: Option<String>,
+    limit: u32,
+) -> Result<Page<ProjectRecord>, ServerFnError> {
+    fetch_page::<ProjectFromDB, ProjectRecord>("project", None, older, newer, limit, |p| {
+        page_cursor(&p.id, p.created_at)
+    })
+    .await
+}
+
+#[leptos::server(GetBlogsPage, "/api")]
+pub async fn get_blogs_page(
+    older: Option<String>,
+    newer: Option<String>,
+    limit: u32,
+) -> Result<Page<BlogRecord>, ServerFnError> {
+    fetch_page::<BlogFromDB, BlogRecord>("blog_post", None, older, newer, limit, |b| {
+        page_cursor(&b.id, b.created_at)
+    })
+    .await
+}
+
+// A tag's archive: the tag itself, and one page of what is tagged with it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaggedPage<T> {
+    pub tag: Tag,
+    pub page: Page<T>,
+}
+
+#[cfg(feature = "ssr")]
+async fn find_tag(slug: &str) -> Result<Option<Tag>, ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+    crate::tags::find_tag(&db, slug)
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})
+}
+
+#[leptos::server(GetTaggedProjectsPage, "/api")]
+pub async fn get_tagged_projects_page(
+    tag: String,
+    older: Option<String>,
+    newer: Option<String>,
+    limit: u32,
+) -> Result<Option<TaggedPage<ProjectRecord>>, ServerFnError> {
+    let Some(tag) = find_tag(&tag).await? else { return Ok(None) };
+    let page = fetch_page::<ProjectFromDB, ProjectRecord>("project", Some(&tag.slug), older, newer, limit, |p| {
+        page_cursor(&p.id, p.created_at)
+    })
+    .await?;
+    Ok(Some(TaggedPage { tag, page }))
+}
+
+#[leptos::server(GetTaggedBlogsPage, "/api")]
+pub async fn get_tagged_blogs_page(
+    tag: String,
+    older: Option<String>,
+    newer: Option<String>,
+    limit: u32,
+) -> Result<Option<TaggedPage<BlogRecord>>, ServerFnError> {
+    let Some(tag) = find_tag(&tag).await? else { return Ok(None) };
+    let page = fetch_page::<BlogFromDB, BlogRecord>("blog_post", Some(&tag.slug), older, newer, limit, |b| {
+        page_cursor(&b.id, b.created_at)
+    })
+    .await?;
+    Ok(Some(TaggedPage { tag, page }))
 }
 
 #[leptos::server(GetProjects, "/api")]
@@ -52,110 +558,891 @@ pub async fn get_projects() -> Result<Vec<ProjectRecord>, ServerFnError> {
     // The code inside this function only runs on the server.
     use surrealdb::engine::local::Db;
     use surrealdb::Surreal;
-    use surrealdb::sql::Thing;
-    use serde::Deserialize;
 
-    // This struct is only used by the server function, so it's fine to keep it inside.
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+
+    let mut response = db
+        .query(format!("SELECT {RECORD_FIELDS} FROM project WHERE {PUBLICLY_VISIBLE} ORDER BY created_at DESC"))
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let projects1: Vec<ProjectFromDB> = response
+        .take(0)
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+
+    Ok(projects1.into_iter().map(|p| ProjectRecord::from(p).for_listing()).collect())
+}
+// Every project whatever its status, for the admin panel.
+#[leptos::server(GetAllProjects, "/api/admin", client = CsrfClient)]
+pub async fn get_all_projects() -> Result<Vec<ProjectRecord>, ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+
+    let mut response = db
+        .query(format!("SELECT {RECORD_FIELDS} FROM project ORDER BY created_at DESC"))
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let projects1: Vec<ProjectFromDB> = response
+        .take(0)
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+
+    Ok(projects1.into_iter().map(ProjectRecord::from).collect())
+}
+#[leptos::server(GetProjectBySlug, "/api")]
+pub async fn get_project_by_slug(slug: String) -> Result<Option<ProjectRecord>, ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+
+    let mut response = db
+        .query(format!("SELECT {RECORD_FIELDS} FROM project WHERE slug = $slug AND {PUBLICLY_VISIBLE} LIMIT 1"))
+        .bind(("slug", slug))
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let project: Option<ProjectFromDB> = response
+        .take(0)
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+
+    Ok(project.map(|p| ProjectRecord::from(p).rendered()))
+}
+#[leptos::server(GetBlogs, "/api")]
+pub async fn get_blogs() -> Result<Vec<BlogRecord>, ServerFnError> {
+    // The code inside this function only runs on the server.
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+
+    let mut response = db
+        .query(format!("SELECT {RECORD_FIELDS} FROM blog_post WHERE {PUBLICLY_VISIBLE} ORDER BY created_at DESC"))
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let blogs1: Vec<BlogFromDB> = response
+        .take(0)
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+
+    Ok(blogs1.into_iter().map(|b| BlogRecord::from(b).for_listing()).collect())
+}
+// Every blog post whatever its status, for the admin panel.
+#[leptos::server(GetAllBlogs, "/api/admin", client = CsrfClient)]
+pub async fn get_all_blogs() -> Result<Vec<BlogRecord>, ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+
+    let mut response = db
+        .query(format!("SELECT {RECORD_FIELDS} FROM blog_post ORDER BY created_at DESC"))
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let blogs1: Vec<BlogFromDB> = response
+        .take(0)
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+
+    Ok(blogs1.into_iter().map(BlogRecord::from).collect())
+}
+#[leptos::server(GetBlogBySlug, "/api")]
+pub async fn get_blog_by_slug(slug: String) -> Result<Option<BlogRecord>, ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+
+    let mut response = db
+        .query(format!("SELECT {RECORD_FIELDS} FROM blog_post WHERE slug = $slug AND {PUBLICLY_VISIBLE} LIMIT 1"))
+        .bind(("slug", slug))
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let blog: Option<BlogFromDB> = response
+        .take(0)
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+
+    Ok(blog.map(|b| BlogRecord::from(b).rendered()))
+}
+// --- Search ---
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchKind {
+    Blog,
+    Project,
+}
+
+impl SearchKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchKind::Blog => "View",
+            SearchKind::Project => "Project",
+        }
+    }
+}
+
+// The title and snippet are HTML with the matched terms in `<mark>` tags.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SearchResult {
+    pub kind: SearchKind,
+    pub href: String,
+    pub title_html: String,
+    pub snippet_html: String,
+}
+
+#[cfg(feature = "ssr")]
+const MAX_SEARCH_RESULTS: usize = 20;
+#[cfg(feature = "ssr")]
+const MAX_SEARCH_QUERY_CHARS: usize = 200;
+
+// Publicly visible posts and projects matching `q`, best matches first.
+#[leptos::server(SearchContent, "/api")]
+pub async fn search_content(q: String) -> Result<Vec<SearchResult>, ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+    use crate::search::{has_match, highlight_html, snippet_html, MATCH_END, MATCH_START};
+
     #[derive(Deserialize, Debug)]
-    struct ProjectFromDB {
-        id: Thing,
+    struct SearchHit {
+        slug: String,
         title: String,
-        content: String,
-        link: String,
+        #[serde(default)]
+        content: Option<String>,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        score: f64,
+    }
+
+    let q: String = q.trim().chars().take(MAX_SEARCH_QUERY_CHARS).collect();
+    if q.is_empty() {
+        return Ok(Vec::new());
     }
 
     let db = use_context::<Surreal<Db>>()
         .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
 
-    let projects1: Vec<ProjectFromDB> = db
-        .select("project")
+    let mut response = db
+        .query(format!(
+            "SELECT slug, search::highlight($open, $close, 0) AS title, \
+             search::highlight($open, $close, 1) AS content, \
+             (search::score(0) ?? 0) + (search::score(1) ?? 0) AS score \
+             FROM blog_post WHERE (title @0@ $q OR content @1@ $q) AND {PUBLICLY_VISIBLE} \
+             ORDER BY score DESC LIMIT $limit"
+        ))
+        .query(format!(
+            "SELECT slug, search::highlight($open, $close, 0) AS title, \
+             search::highlight($open, $close, 1) AS content, \
+             search::highlight($open, $close, 2) AS description, \
+             (search::score(0) ?? 0) + (search::score(1) ?? 0) + (search::score(2) ?? 0) AS score \
+             FROM project WHERE (title @0@ $q OR content @1@ $q OR description @2@ $q) AND {PUBLICLY_VISIBLE} \
+             ORDER BY score DESC LIMIT $limit"
+        ))
+        .bind(("q", q))
+        .bind(("open", MATCH_START.to_string()))
+        .bind(("close", MATCH_END.to_string()))
+        .bind(("limit", MAX_SEARCH_RESULTS))
         .await
         .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let blogs: Vec<SearchHit> = response
+        .take(0)
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let projects: Vec<SearchHit> = response
+        .take(1)
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
 
-    let projects = projects1
-        .into_iter()
-        .map(|p| ProjectRecord {
-            id: p.id.to_string(), // The crucial conversion!
-            title: p.title,
-            content: p.content,
-            link: p.link,
+    let to_result = |kind: SearchKind, hit: SearchHit| {
+        let content = hit.content.unwrap_or_default();
+        // A project's snippet comes from its case study when only that matched
+        let body = match hit.description {
+            Some(description) if !has_match(&content) && has_match(&description) => description,
+            _ => content,
+        };
+        let href = match kind {
+            SearchKind::Blog => format!("/views/{}", hit.slug),
+            SearchKind::Project => format!("/projects/{}", hit.slug),
+        };
+        (hit.score, SearchResult {
+            kind,
+            href,
+            title_html: highlight_html(&hit.title),
+            snippet_html: snippet_html(&body),
         })
+    };
+    let mut results: Vec<(f64, SearchResult)> = blogs
+        .into_iter()
+        .map(|hit| to_result(SearchKind::Blog, hit))
+        .chain(projects.into_iter().map(|hit| to_result(SearchKind::Project, hit)))
         .collect();
+    results.sort_by(|a, b| b.0.total_cmp(&a.0));
 
-    Ok(projects)
+    Ok(results
+        .into_iter()
+        .take(MAX_SEARCH_RESULTS)
+        .map(|(_, result)| result)
+        .collect())
 }
-#[leptos::server(GetBlogs, "/api")]
-pub async fn get_blogs() -> Result<Vec<BlogRecord>, ServerFnError> {
-    // The code inside this function only runs on the server.
+
+// Record ids reach the client as `table:key` (see `BlogRecord::from`), so accept either form back.
+#[cfg(feature = "ssr")]
+fn record_id(table: &str, id: &str) -> surrealdb::RecordId {
+    let key = id
+        .strip_prefix(table)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .unwrap_or(id);
+    surrealdb::RecordId::from((table, key))
+}
+
+#[leptos::server(DeleteProject, "/api/admin", client = CsrfClient)]
+pub async fn delete_project(id: String) -> Result<(), ServerFnError> {
     use surrealdb::engine::local::Db;
     use surrealdb::Surreal;
-    use surrealdb::sql::Thing;
-    use serde::Deserialize;
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+    let record_id = record_id("project", &id);
+    let _deleted: Option<ProjectRecord> = db.delete(record_id)
+        .await?;
 
-    // This struct is only used by the server function, so it's fine to keep it inside.
-    #[derive(Deserialize, Debug)]
-    struct BlogFromDB {
+    Ok(())
+}
+
+#[leptos::server(DeleteBlog, "/api/admin", client = CsrfClient)]
+pub async fn delete_blog(id: String) -> Result<(), ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+    let record_id = record_id("blog_post", &id);
+    let _deleted: Option<BlogRecord> = db.delete(record_id)
+        .await?;
+
+    Ok(())
+}
+
+#[leptos::server(UpdateProject, "/api/admin", client = CsrfClient)]
+pub async fn update_project(id: String, project: Project) -> Result<(), ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+    use surrealdb::sql::{Datetime, Thing};
+    use serde::{Deserialize, Serialize};
+    use crate::tags::set_tags;
+
+    #[derive(Serialize)]
+    struct ProjectChanges {
+        title: String,
+        content: String,
+        link: String,
+        description: String,
+        status: PublishStatus,
+        publish_at: Option<Datetime>,
+        cover_image: Option<String>,
+        updated_at: Datetime,
+    }
+    #[derive(Deserialize)]
+    struct UpdatedProject {
+        #[allow(dead_code)]
         id: Thing,
+    }
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+    let Project { title, content, link, description, status, publish_at, tags, cover_image } = project;
+    let publish_at = validate_publish_at(status, publish_at.as_deref()).map_err(|e|->ServerFnError{ ServerFnError::ServerError(e)})?;
+    let changes = ProjectChanges {
+        title,
+        content,
+        link,
+        description,
+        status,
+        publish_at,
+        cover_image,
+        updated_at: Datetime::default(), // now
+    };
+    let updated: Option<UpdatedProject> = db
+        .update(record_id("project", &id))
+        .merge(changes)
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    if updated.is_none() {
+        return Err(ServerFnError::ServerError(format!("Project {} not found", id)));
+    }
+
+    set_tags(&db, record_id("project", &id), &tags)
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})
+}
+
+#[leptos::server(UpdateBlog, "/api/admin", client = CsrfClient)]
+pub async fn update_blog(id: String, blog: BlogPost) -> Result<(), ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+    use surrealdb::sql::{Datetime, Thing};
+    use serde::{Deserialize, Serialize};
+    use crate::tags::set_tags;
+
+    #[derive(Serialize)]
+    struct BlogChanges {
         title: String,
         content: String,
+        status: PublishStatus,
+        publish_at: Option<Datetime>,
+        updated_at: Datetime,
+    }
+    #[derive(Deserialize)]
+    struct UpdatedBlog {
+        #[allow(dead_code)]
+        id: Thing,
     }
 
     let db = use_context::<Surreal<Db>>()
         .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+    let BlogPost { title, content, status, publish_at, tags } = blog;
+    let publish_at = validate_publish_at(status, publish_at.as_deref()).map_err(|e|->ServerFnError{ ServerFnError::ServerError(e)})?;
+    let changes = BlogChanges {
+        title,
+        content,
+        status,
+        publish_at,
+        updated_at: Datetime::default(), // now
+    };
+    let updated: Option<UpdatedBlog> = db
+        .update(record_id("blog_post", &id))
+        .merge(changes)
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    if updated.is_none() {
+        return Err(ServerFnError::ServerError(format!("Blog post {} not found", id)));
+    }
+
+    set_tags(&db, record_id("blog_post", &id), &tags)
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})
+}
+#[leptos::server(LogoutEverywhere, "/api/admin", client = CsrfClient)]
+pub async fn logout_everywhere() -> Result<(), ServerFnError> {
+    use axum::Extension;
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+    use tower_cookies::Cookies;
+    use crate::auth::{delete_user_sessions, removal_cookie, Session};
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+    // The session is put in the request extensions by `auth_middleware`
+    let Extension(session): Extension<Session> = leptos_axum::extract().await?;
+    let cookies: Cookies = leptos_axum::extract().await?;
 
-    let blogs1: Vec<BlogFromDB> = db
-        .select("blog_post")
+    delete_user_sessions(&db, &session.user)
         .await
         .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    cookies.add(removal_cookie());
 
-    let blogs = blogs1
-        .into_iter()
-        .map(|p| BlogRecord {
-            id: p.id.to_string(), // The crucial conversion!
-            title: p.title,
-            content: p.content,
+    Ok(())
+}
+
+// --- Password Reset ---
+// Both have their own endpoints so main.rs can rate limit them more tightly, see
+// `crate::password_reset`.
+#[leptos::server(name = RequestPasswordReset, prefix = "/api", endpoint = "forgot-password")]
+pub async fn request_password_reset(email: String) -> Result<(), ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+
+    // Everyone is told the same thing, whether or not the address has an account
+    crate::password_reset::request_password_reset(&db, &email);
+    Ok(())
+}
+
+#[leptos::server(name = ResetPassword, prefix = "/api", endpoint = "reset-password")]
+pub async fn reset_password(token: String, password: String) -> Result<(), ServerFnError> {
+    use leptos::logging::log;
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+    use crate::password_reset::ResetError;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+
+    crate::password_reset::reset_password(&db, &token, &password)
+        .await
+        .map_err(|e| {
+            if let ResetError::Storage(reason) = &e {
+                log!("Failed to reset a password: {}", reason);
+            }
+            ServerFnError::ServerError(e.message())
         })
-        .collect();
+}
+
+// --- Email Verification ---
+// Follows a link from a verification email; rate limited like the password reset endpoints.
+#[leptos::server(name = VerifyEmail, prefix = "/api", endpoint = "verify-email")]
+pub async fn verify_email(token: String) -> Result<(), ServerFnError> {
+    use leptos::logging::log;
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+    use crate::password_reset::ResetError;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+
+    crate::password_reset::verify_email(&db, &token)
+        .await
+        .map_err(|e| match e {
+            ResetError::Storage(reason) => {
+                log!("Failed to verify an email address: {}", reason);
+                ServerFnError::ServerError("Your email address couldn't be confirmed. Please try again later.".to_string())
+            }
+            _ => ServerFnError::ServerError(
+                "This link has expired or has already been used. Please ask for a new one from the admin panel.".to_string(),
+            ),
+        })
+}
+
+// How long a confirmation link works, see `crate::password_reset::VERIFY_TOKEN_TTL`
+pub const VERIFY_LINK_HOURS: u64 = 24;
+
+// The signed-in account's email address and whether it has been confirmed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AccountEmail {
+    pub email: String,
+    pub verified: bool,
+}
+
+#[leptos::server(GetAccountEmail, "/api/admin", client = CsrfClient)]
+pub async fn get_account_email() -> Result<AccountEmail, ServerFnError> {
+    use axum::Extension;
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+    use crate::auth::{find_user, Session};
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+    let Extension(session): Extension<Session> = leptos_axum::extract().await?;
+
+    let user = find_user(&db, &session.user)
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let user = user.ok_or_else(|| ServerFnError::ServerError("Account not found".to_string()))?;
+    Ok(AccountEmail { email: user.email, verified: user.email_verified_at.is_some() })
+}
+
+#[leptos::server(SendVerificationEmail, "/api/admin", client = CsrfClient)]
+pub async fn send_verification_email() -> Result<(), ServerFnError> {
+    use axum::Extension;
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+    use crate::auth::{find_user, Session};
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+    let Extension(session): Extension<Session> = leptos_axum::extract().await?;
+
+    let user = find_user(&db, &session.user)
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let user = user.ok_or_else(|| ServerFnError::ServerError("Account not found".to_string()))?;
+    crate::password_reset::send_verification_email(&db, &user.id, &user.email)
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})
+}
+
+// --- Media ---
+// Everything uploaded through `/api/admin/upload`, newest first, for the admin panel's media library.
+#[leptos::server(GetMedia, "/api/admin", client = CsrfClient)]
+pub async fn get_media() -> Result<Vec<MediaRecord>, ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+
+    let mut response = db
+        .query("SELECT * FROM media ORDER BY created_at DESC")
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let media: Vec<MediaFromDB> = response
+        .take(0)
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+
+    Ok(media.into_iter().map(MediaRecord::from).collect())
+}
+
+// --- Contact Messages ---
+// A message sent through the contact form, for the admin panel's inbox.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContactMessage {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    pub message: String,
+    pub read: bool,
+    pub archived: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+// As stored in the `contact_message` table (server only)
+#[cfg(feature = "ssr")]
+#[derive(Deserialize, Debug)]
+struct ContactMessageFromDB {
+    id: surrealdb::sql::Thing,
+    name: String,
+    email: String,
+    message: String,
+    read: bool,
+    archived: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+#[cfg(feature = "ssr")]
+impl From<ContactMessageFromDB> for ContactMessage {
+    fn from(m: ContactMessageFromDB) -> Self {
+        ContactMessage {
+            id: m.id.to_string(),
+            name: m.name,
+            email: m.email,
+            message: m.message,
+            read: m.read,
+            archived: m.archived,
+            created_at: m.created_at,
+        }
+    }
+}
+
+// A signed note of when the contact form was shown, sent back with the message; see `crate::contact`.
+#[leptos::server(GetContactFormToken, "/api")]
+pub async fn get_contact_form_token() -> Result<String, ServerFnError> {
+    Ok(crate::contact::issue_form_token())
+}
+
+// At its own endpoint so main.rs can give it a tighter rate limit than the rest of `/api`.
+// `website` is the honeypot, and should always be empty.
+#[leptos::server(name = SubmitContactMessage, prefix = "/api", endpoint = "contact")]
+pub async fn submit_contact_message(
+    name: String,
+    email: String,
+    message: String,
+    website: String,
+    form_token: String,
+) -> Result<(), ServerFnError> {
+    use leptos::logging::log;
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+    use crate::contact::{submit_message, ContactError};
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+
+    match submit_message(&db, &name, &email, &message, &website, &form_token).await {
+        Ok(true) => Ok(()),
+        // Bots are told it worked, so they don't learn to get round it
+        Ok(false) => {
+            log!("Dropped a contact message that filled in the honeypot");
+            Ok(())
+        }
+        Err(e) => {
+            if let ContactError::Storage(reason) = &e {
+                log!("Failed to store contact message: {}", reason);
+            }
+            Err(ServerFnError::ServerError(e.message()))
+        }
+    }
+}
+
+// The inbox, or the archive, newest first.
+#[leptos::server(GetContactMessages, "/api/admin", client = CsrfClient)]
+pub async fn get_contact_messages(archived: bool) -> Result<Vec<ContactMessage>, ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+
+    let mut response = db
+        .query("SELECT * FROM contact_message WHERE archived = $archived ORDER BY created_at DESC")
+        .bind(("archived", archived))
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let messages: Vec<ContactMessageFromDB> = response
+        .take(0)
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+
+    Ok(messages.into_iter().map(ContactMessage::from).collect())
+}
+
+// Marks a message read or unread, and moves it in or out of the archive.
+#[leptos::server(UpdateContactMessage, "/api/admin", client = CsrfClient)]
+pub async fn update_contact_message(id: String, read: bool, archived: bool) -> Result<(), ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    #[derive(Serialize)]
+    struct MessageChanges {
+        read: bool,
+        archived: bool,
+    }
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+    let updated: Option<ContactMessageFromDB> = db
+        .update(record_id("contact_message", &id))
+        .merge(MessageChanges { read, archived })
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    if updated.is_none() {
+        return Err(ServerFnError::ServerError(format!("Message {} not found", id)));
+    }
+
+    Ok(())
+}
+
+#[leptos::server(DeleteContactMessage, "/api/admin", client = CsrfClient)]
+pub async fn delete_contact_message(id: String) -> Result<(), ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+    let _deleted: Option<ContactMessageFromDB> = db
+        .delete(record_id("contact_message", &id))
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+
+    Ok(())
+}
+
+// --- Site Settings ---
+// Saves the settings edited in the admin panel, see `crate::settings`. Returns them as stored.
+#[leptos::server(UpdateSiteSettings, "/api/admin", client = CsrfClient)]
+pub async fn update_site_settings(settings: SiteSettings) -> Result<SiteSettings, ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+    use crate::settings::save_site_settings;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+    let settings = settings.validate().map_err(|e|->ServerFnError{ ServerFnError::ServerError(e)})?;
+
+    save_site_settings(&db, settings.clone())
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+
+    Ok(settings)
+}
+
+// --- Home Sections ---
+// One block of text on the home page's wheel, see `crate::home`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HomeSection {
+    pub id: String,
+    pub paragraphs: Vec<String>,
+}
+
+// As stored in the `home_section` table (server only)
+#[cfg(feature = "ssr")]
+#[derive(Deserialize, Debug)]
+struct HomeSectionFromDB {
+    id: surrealdb::sql::Thing,
+    paragraphs: Vec<String>,
+    #[allow(dead_code)]
+    position: u32,
+}
+
+#[cfg(feature = "ssr")]
+impl From<HomeSectionFromDB> for HomeSection {
+    fn from(s: HomeSectionFromDB) -> Self {
+        HomeSection {
+            id: s.id.to_string(),
+            paragraphs: s.paragraphs,
+        }
+    }
+}
+
+// The sections in the order they go round the wheel.
+#[leptos::server(GetHomeSections, "/api")]
+pub async fn get_home_sections() -> Result<Vec<HomeSection>, ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+
+    let mut response = db
+        .query("SELECT id, paragraphs, position, created_at FROM home_section ORDER BY position, created_at")
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let sections: Vec<HomeSectionFromDB> = response
+        .take(0)
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+
+    Ok(sections.into_iter().map(HomeSection::from).collect())
+}
+
+// Adds a section at the end of the wheel. Paragraphs are separated by blank lines.
+#[leptos::server(CreateHomeSection, "/api/admin", client = CsrfClient)]
+pub async fn create_home_section(paragraphs: Vec<String>) -> Result<(), ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+    use surrealdb::sql::Datetime;
+    use crate::home::{clean_paragraphs, NewHomeSection};
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+    let paragraphs = clean_paragraphs(paragraphs);
+    if paragraphs.is_empty() {
+        return Err(ServerFnError::ServerError("A section needs some text.".to_string()));
+    }
+
+    let mut response = db
+        .query("SELECT VALUE position FROM home_section ORDER BY position DESC LIMIT 1")
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    let last: Option<u32> = response
+        .take(0)
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+
+    let now = Datetime::default();
+    let _created: Option<serde::de::IgnoredAny> = db
+        .create("home_section")
+        .content(NewHomeSection {
+            paragraphs,
+            position: last.map_or(0, |position| position + 1),
+            created_at: now.clone(),
+            updated_at: now,
+        })
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+
+    Ok(())
+}
+
+#[leptos::server(UpdateHomeSection, "/api/admin", client = CsrfClient)]
+pub async fn update_home_section(id: String, paragraphs: Vec<String>) -> Result<(), ServerFnError> {
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+    use surrealdb::sql::Datetime;
+    use crate::home::clean_paragraphs;
+
+    #[derive(Serialize)]
+    struct SectionChanges {
+        paragraphs: Vec<String>,
+        updated_at: Datetime,
+    }
+
+    let db = use_context::<Surreal<Db>>()
+        .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
+    let paragraphs = clean_paragraphs(paragraphs);
+    if paragraphs.is_empty() {
+        return Err(ServerFnError::ServerError("A section needs some text.".to_string()));
+    }
+
+    let updated: Option<HomeSectionFromDB> = db
+        .update(record_id("home_section", &id))
+        .merge(SectionChanges { paragraphs, updated_at: Datetime::default() })
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
+    if updated.is_none() {
+        return Err(ServerFnError::ServerError(format!("Section {} not found", id)));
+    }
 
-    Ok(blogs)
+    Ok(())
 }
-#[leptos::server(DeleteProject, "/api/admin")]
-pub async fn delete_project(id: String) -> Result<(), ServerFnError> {
+
+#[leptos::server(DeleteHomeSection, "/api/admin", client = CsrfClient)]
+pub async fn delete_home_section(id: String) -> Result<(), ServerFnError> {
     use surrealdb::engine::local::Db;
     use surrealdb::Surreal;
-    use surrealdb::RecordId;
+
     let db = use_context::<Surreal<Db>>()
         .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
-    let record_id = RecordId::from(("project", id));
-    let _deleted: Option<ProjectRecord> = db.delete(record_id)
-        .await?;
+    let _deleted: Option<HomeSectionFromDB> = db
+        .delete(record_id("home_section", &id))
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
 
     Ok(())
 }
 
-#[leptos::server(DeleteBlog, "/api/admin")]
-pub async fn delete_blog(id: String) -> Result<(), ServerFnError> {
+// Puts the sections in the order of `ids`, which must list every section exactly once.
+#[leptos::server(ReorderHomeSections, "/api/admin", client = CsrfClient)]
+pub async fn reorder_home_sections(ids: Vec<String>) -> Result<(), ServerFnError> {
     use surrealdb::engine::local::Db;
     use surrealdb::Surreal;
-    use surrealdb::RecordId;
+    use crate::home::reorder_sections;
 
     let db = use_context::<Surreal<Db>>()
         .ok_or_else(||->ServerFnError{ ServerFnError::ServerError("Database not provided".to_string())})?;
-    let record_id = RecordId::from(("blog_post", id));
-    let _deleted: Option<BlogRecord> = db.delete(record_id)
-        .await?;
+
+    let ids: Vec<surrealdb::RecordId> = ids.iter().map(|id| record_id("home_section", id)).collect();
+    reorder_sections(&db, &ids)
+        .await
+        .map_err(|e|->ServerFnError{ ServerFnError::ServerError(e.to_string())})?;
 
     Ok(())
 }
+
+// Posts or projects per page on the public listings
+const PAGE_SIZE: u32 = 9;
+
 #[derive(Clone, PartialEq)]
 struct ContentSection {
-    id: u32,
+    id: String,
     paragraphs: Vec<String>,
     base_transform_style: String,
     initial_static_rotation_deg: f64,
 }
 
+// How far from the wheel's centre the sections sit. The wheel is wider than it is tall, like
+// the screen, so they stay clear of each other either way.
+const WHEEL_RADIUS_VW: f64 = 36.0;
+const WHEEL_RADIUS_VH: f64 = 25.0;
+
+// Places the section at `index` of `count` on the wheel: spread evenly round it, starting at
+// the top, each turned by its angle so it reads upright when the wheel brings it up there.
+fn wheel_placement(index: usize, count: usize) -> (String, f64) {
+    let angle = 360.0 * index as f64 / count.max(1) as f64;
+    let radians = angle.to_radians();
+    let x = WHEEL_RADIUS_VW * radians.sin();
+    let y = WHEEL_RADIUS_VH * (1.0 - radians.cos());
+    (format!("transform: translateX({:.2}vw) translateY({:.2}vh) rotate({}deg);", x, y, angle), angle)
+}
+
+fn content_sections(sections: Vec<HomeSection>) -> Vec<ContentSection> {
+    let count = sections.len();
+    sections
+        .into_iter()
+        .enumerate()
+        .map(|(index, section)| {
+            let (base_transform_style, initial_static_rotation_deg) = wheel_placement(index, count);
+            ContentSection {
+                id: section.id,
+                paragraphs: section.paragraphs,
+                base_transform_style,
+                initial_static_rotation_deg,
+            }
+        })
+        .collect()
+}
+
 pub fn shell(options: LeptosOptions) -> impl IntoView {
+    // Signed-in admins get their session's CSRF token, for `crate::csrf` to send back
+    #[cfg(feature = "ssr")]
+    let csrf_meta = use_context::<axum::http::request::Parts>()
+        .and_then(|parts| parts.extensions.get::<crate::auth::Session>().cloned())
+        .map(|session| view! { <meta name="csrf-token" content=session.csrf_token/> });
+    #[cfg(not(feature = "ssr"))]
+    let csrf_meta: Option<View<()>> = None;
+    // Read back by `site_settings` in the browser
+    let settings_json = site_settings().to_embedded_json();
+
     view! {
         <!DOCTYPE html>
         <html lang="en">
@@ -165,6 +1452,8 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
                 <AutoReload options=options.clone() />
                 <HydrationScripts options/>
                 <MetaTags/>
+                {csrf_meta}
+                <script id=SETTINGS_ELEMENT_ID type="application/json" inner_html=settings_json></script>
             </head>
             <body>
                 <App/>
@@ -176,10 +1465,17 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
 #[component]
 pub fn App() -> impl IntoView {
     provide_meta_context();
+    // Updated by the admin panel when the settings are saved
+    let settings = RwSignal::new(site_settings());
+    provide_context(settings);
 
     view! {
         <Stylesheet id="leptos" href="/pkg/shilohnova.css"/>
-        <Title text="Shiloh Antony John"/>
+        <Stylesheet id="highlight" href="/pkg/highlight.css"/>
+        <Title text=move || settings.get().site_title/>
+        <Link rel="alternate" type_="application/rss+xml" title="Views" href="/feed.xml"/>
+        <Link rel="alternate" type_="application/atom+xml" title="Views" href="/atom.xml"/>
+        <Link rel="alternate" type_="application/feed+json" title="Views" href="/feed.json"/>
 
         <Router>
             <div class="fixed top-0 left-0 w-full p-4 flex justify-between items-center bg-sandy-beige text-navy-blue-custom text-xl z-50 ">
@@ -187,10 +1483,11 @@ pub fn App() -> impl IntoView {
                     <A href="/projects">"Projects"</A>
                 </div>
                 <div class="flex-1 text-center text-3xl font-bold px-4">
-                    <A href="/">"Shiloh Antony John"</A>
+                    <A href="/">{move || settings.get().site_title}</A>
                 </div>
-                <div class="flex-1 text-right px-4">
+                <div class="flex-1 flex justify-end items-center gap-4 px-4">
                     <A href="/views">"Views"</A>
+                    <SearchBox/>
                 </div>
             </div>
 
@@ -198,9 +1495,17 @@ pub fn App() -> impl IntoView {
                 <Routes fallback=|| "Page not found.".into_view()>
                     <Route path=StaticSegment("") view=HomePage/>
                     <Route path=StaticSegment("projects") view=Projects/>
+                    <Route path=(StaticSegment("projects"), ParamSegment("slug")) view=ProjectPage ssr=SsrMode::Async/>
+                    <Route path=(StaticSegment("projects"), StaticSegment("tag"), ParamSegment("tag")) view=TaggedProjects ssr=SsrMode::Async/>
                     <Route path=StaticSegment("views") view=Views/>
+                    <Route path=(StaticSegment("views"), ParamSegment("slug")) view=BlogPostPage ssr=SsrMode::Async/>
+                    <Route path=(StaticSegment("views"), StaticSegment("tag"), ParamSegment("tag")) view=TaggedViews ssr=SsrMode::Async/>
+                    <Route path=StaticSegment("search") view=SearchPage/>
                     <Route path=StaticSegment("contacts") view=Contacts/>
                     <Route path=StaticSegment("login") view=LoginPage/>
+                    <Route path=(StaticSegment("login"), StaticSegment("forgot")) view=ForgotPasswordPage/>
+                    <Route path=(StaticSegment("login"), StaticSegment("reset"), ParamSegment("token")) view=ResetPasswordPage/>
+                    <Route path=(StaticSegment("login"), StaticSegment("verify"), ParamSegment("token")) view=VerifyEmailPage/>
                     <Route path=StaticSegment("adminpanel") view=AdminPanel/>
                 </Routes>
             </main>
@@ -213,43 +1518,15 @@ fn HomePage() -> impl IntoView {
     let main_container_rotation = RwSignal::new(0.0);
     let touch_start_y = RwSignal::new(0.0); // New signal to track touch start position
 
-    let sections = vec![
-        ContentSection {
-            id: 1,
-            paragraphs: vec![
-                "Hi, I am Shiloh Antony John, a Stoic programmer who aspires to be an entrepreneur to do good to humanity. I am obsessed with gaining knowledge and solving problems with true innovation! Continue scrolling!!!".to_string(),
-
-            ],
-            base_transform_style: "transform: translateY(0vh) rotate(0deg);".to_string(),
-            initial_static_rotation_deg: 0.0,
-        },
-        ContentSection {
-            id: 2,
-            paragraphs: vec![
-                "I operate at the digital frontier, where blockchain's revolutionary promise and emerging technologies like AI and smart contracts redefine human trust and commerce.".to_string(),
-
-            ],
-            base_transform_style: "transform: translateX(35vw) rotate(90deg);".to_string(),
-            initial_static_rotation_deg: 90.0,
-        },
-        ContentSection {
-            id: 3,
-            paragraphs: vec![
-                "I leverage the unparalleled efficiency of Rust and the elegant simplicity of Python to engineer cutting-edge solutions that redefine what's possible in the tech world.".to_string(),
-            ],
-            base_transform_style: "transform: translateY(50vh) rotate(180deg);".to_string(),
-            initial_static_rotation_deg: 180.0,
-        },
-        ContentSection {
-            id: 4,
-            paragraphs: vec![
-                "Linux is the bedrock of my development, fueling innovation and scaling my entrepreneurial ventures with its open-source power.".to_string(),
-            ],
-            base_transform_style: "transform: translateX(-37vw) translateY(7vh) rotate(270deg);".to_string(),
-            initial_static_rotation_deg: 270.0,
-        },
-    ];
-    let sections_for_mobile=sections.clone();
+    let settings = use_site_settings().get_untracked();
+    let home_sections = Resource::new(|| (), |_| async move { get_home_sections().await });
+    let sections = move || {
+        home_sections
+            .get()
+            .and_then(Result::ok)
+            .map(content_sections)
+            .unwrap_or_default()
+    };
     let handle_scroll = move |event: WheelEvent| {
         let delta_y = event.delta_y();
         let rotation_speed_factor = 1.5;
@@ -286,14 +1563,17 @@ fn HomePage() -> impl IntoView {
         }
     };
 
-    view! {<div class="w-screen flex justify-center items-center bg-sandy-beige  overflow-hidden" on:wheel=handle_scroll on:touchstart=handle_touch_start on:touchmove=handle_touch_move>
+    view! {
+        <PageMeta title=settings.site_title description=settings.tagline/>
+        <div class="w-screen flex justify-center items-center bg-sandy-beige  overflow-hidden" on:wheel=handle_scroll on:touchstart=handle_touch_start on:touchmove=handle_touch_move>
             <div // desktop layout
                 class="h-screen hidden md:flex transition-transform duration-100 ease-out h-[200vh] w-[200vw] relative flex justify-center items-center text-navy-blue-custom text-2xl md:text-2xl"
                 style=move || format!("transform: rotate({}deg);", main_container_rotation.get())
             >
+                <Suspense>
                 <For
-                    each=move || sections.clone()
-                    key=|section| section.id
+                    each=sections
+                    key=|section| section.id.clone()
                     children=move |section| {
                         let current_main_rotation_signal = main_container_rotation;
                         view! {
@@ -321,14 +1601,16 @@ fn HomePage() -> impl IntoView {
                         }
                     }
                 />
+                </Suspense>
             </div>
         // --- Mobile Layout ---
             // This container is visible by default and hidden on medium screens and up (`md:hidden`).
 
             <div class="md:hidden flex flex-col items-center p-8 gap-12 text-center text-xl pt-20 min-h-screen">
+                <Suspense>
                 <For
-                    each=move || sections_for_mobile.clone()
-                    key=|section| section.id
+                    each=sections
+                    key=|section| section.id.clone()
                     children=move |section| {
                         view! {
                             <div>
@@ -341,6 +1623,7 @@ fn HomePage() -> impl IntoView {
                         }
                     }
                 />
+                </Suspense>
             </div>
         </div>
         <div class="fixed bottom-2  left-1/2 -translate-x-1/2 text-xl font-bold z-10">
@@ -351,40 +1634,36 @@ fn HomePage() -> impl IntoView {
 
 #[component]
 fn Projects() -> impl IntoView {
+    let owner_name = use_site_settings().get_untracked().owner_name;
 
-    let location=use_location();
+    // The page is in the query string, so it can be bookmarked and rendered on the server
+    let query = use_query_map();
 
     let projects = Resource::new(
-        move || location.pathname.get(),
-        |_| async move {
+        move || query.with(|q| (q.get("older"), q.get("newer"))),
+        |(older, newer)| async move {
             {
-                get_projects().await
+                get_projects_page(older, newer, PAGE_SIZE).await
             }
 
         },
     );
 
     view! {
+    <PageMeta title="Projects" description=format!("Projects by {}.", owner_name)/>
     <div class="container mx-auto p-4 md:p-8 min-h-screen pt-20">
         <Suspense fallback=|| view! { <p>"Loading projects..."</p> }>
             {move || {
-                projects.read().clone().map(|res:Result<Vec<ProjectRecord>, ServerFnError>| match res {
-                    Ok(vec) if !vec.is_empty() => view! {
+                projects.read().clone().map(|res:Result<Page<ProjectRecord>, ServerFnError>| match res {
+                    Ok(Page { items, older, newer }) if !items.is_empty() => view! {
                         <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-8">
                             <For
-                                each=move || vec.clone()
+                                each=move || items.clone()
                                 key=|project| project.id.clone()
-                                children=move |project: ProjectRecord| {
-                                    view! {
-                                        <div class="bg-charcoal-custom rounded-lg shadow-lg p-6">
-                                            <h2 class="text-xl font-bold mb-2">{project.title}</h2>
-                                            <p>{project.content}</p>
-                                            <p><a href=project.link target="_blank"  rel="noopener noreferrer">cat working_and_more.txt</a></p>
-                                        </div>
-                                    }
-                                }
+                                children=move |project: ProjectRecord| view! { <ProjectCard project/> }
                             />
                         </div>
+                        <PageNav path="/projects".to_string() older=older newer=newer/>
                     }.into_any(),
 
                     Ok(_) => view! { <p>"No projects found"</p> }.into_any(),
@@ -402,42 +1681,192 @@ fn Projects() -> impl IntoView {
 
 }
 
+// An image with its resized copies, letting the browser pick the smallest that fits `sizes`.
+#[component]
+fn Picture(
+    image: ResponsiveImage,
+    sizes: &'static str,
+    #[prop(into, default = String::new())] alt: String,
+    #[prop(optional)] class: &'static str,
+    #[prop(optional)] eager: bool,
+) -> impl IntoView {
+    let ResponsiveImage { src, srcset, webp_srcset, width, height } = image;
+    let has_srcset = !srcset.is_empty();
+    view! {
+        <picture>
+            {(!webp_srcset.is_empty()).then(|| view! { <source type="image/webp" srcset=webp_srcset sizes=sizes/> })}
+            <img
+                src=src
+                srcset=has_srcset.then_some(srcset)
+                sizes=has_srcset.then_some(sizes)
+                width=width
+                height=height
+                alt=alt
+                loading=if eager { "eager" } else { "lazy" }
+                decoding="async"
+                class=class
+            />
+        </picture>
+    }
+}
+
+#[component]
+fn ProjectCard(project: ProjectRecord) -> impl IntoView {
+    let href = format!("/projects/{}", project.slug);
+    view! {
+        <div class="bg-charcoal-custom rounded-lg shadow-lg p-6">
+            {project.cover.map(|image| view! {
+                <Picture
+                    image=image
+                    sizes="(min-width: 1024px) 33vw, (min-width: 768px) 50vw, 100vw"
+                    class="w-full h-48 object-cover rounded mb-4"
+                />
+            })}
+            <h2 class="text-xl font-bold mb-2"><A href=href.clone()>{project.title}</A></h2>
+            <TagList base="/projects" tags=project.tags/>
+            <p>{project.excerpt}</p>
+            <p class="mt-4"><A href=href>"Read the case study"</A></p>
+            <p><a href=project.link target="_blank"  rel="noopener noreferrer">cat working_and_more.txt</a></p>
+        </div>
+    }
+}
+
+#[component]
+fn TaggedProjects() -> impl IntoView {
+    let owner_name = use_site_settings().get_untracked().owner_name;
+    let params = use_params_map();
+    let query = use_query_map();
+
+    let tagged = Resource::new(
+        move || {
+            let tag = params.read().get("tag").unwrap_or_default();
+            query.with(|q| (tag, q.get("older"), q.get("newer")))
+        },
+        |(tag, older, newer)| async move { get_tagged_projects_page(tag, older, newer, PAGE_SIZE).await },
+    );
+
+    view! {
+    <div class="container mx-auto p-4 md:p-8 min-h-screen pt-20">
+        <Suspense fallback=|| view! { <p>"Loading projects..."</p> }>
+            {move || {
+                tagged.read().clone().map(|res:Result<Option<TaggedPage<ProjectRecord>>, ServerFnError>| match res {
+                    Ok(Some(TaggedPage { tag, page: Page { items, older, newer } })) => {
+                        let listing = if items.is_empty() {
+                            view! { <p>"No projects found"</p> }.into_any()
+                        } else {
+                            view! {
+                                <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-8">
+                                    <For
+                                        each=move || items.clone()
+                                        key=|project| project.id.clone()
+                                        children=move |project: ProjectRecord| view! { <ProjectCard project/> }
+                                    />
+                                </div>
+                            }.into_any()
+                        };
+                        view! {
+                            <PageMeta
+                                title=format!("Projects tagged \"{}\"", tag.name)
+                                description=format!("Projects by {} tagged \"{}\".", owner_name, tag.name)
+                            />
+                            <h1 class="text-3xl font-bold mb-8">{format!("Projects tagged \"{}\"", tag.name)}</h1>
+                            {listing}
+                            <PageNav path=format!("/projects/tag/{}", tag.slug) older=older newer=newer/>
+                        }.into_any()
+                    }
+
+                    Ok(None) => view! { <NotFound/> }.into_any(),
+
+                    Err(e) => view! {
+                        <p>{format!("Error loading projects: {}", e)}</p>
+                    }.into_any(),
+                })
+                .unwrap_or_else(|| view! { <p>"Loading..."</p> }.into_any())
+            }}
+        </Suspense>
+    </div>
+    }
+}
+
+#[component]
+fn ProjectPage() -> impl IntoView {
+    let params = use_params_map();
+
+    let project = Resource::new(
+        move || params.read().get("slug").unwrap_or_default(),
+        |slug| async move { get_project_by_slug(slug).await },
+    );
+
+    view! {
+    <div class="container mx-auto p-4 md:p-8 min-h-screen pt-20">
+        <Suspense fallback=|| view! { <p>"Loading project..."</p> }>
+            {move || {
+                project.read().clone().map(|res:Result<Option<ProjectRecord>, ServerFnError>| match res {
+                    Ok(Some(project)) => view! {
+                        <PageMeta
+                            title=project.title.clone()
+                            description=project.excerpt
+                            image=project.preview_image
+                            og_type="article"
+                        />
+                        <article class="max-w-3xl mx-auto">
+                            {project.cover.map(|image| view! {
+                                <Picture image=image sizes="(min-width: 768px) 768px, 100vw" class="w-full h-auto rounded mb-8" eager=true/>
+                            })}
+                            <h1 class="text-4xl font-bold mb-4">{project.title}</h1>
+                            <TagList base="/projects" tags=project.tags/>
+                            <div class="text-xl mb-8" inner_html=project.content_html></div>
+                            <div class="prose" inner_html=project.description_html></div>
+                            <p class="mt-8"><a href=project.link target="_blank" rel="noopener noreferrer">"Visit the project"</a></p>
+                            <p class="mt-4"><A href="/projects">"Back to all projects"</A></p>
+                        </article>
+                    }.into_any(),
+
+                    Ok(None) => view! { <NotFound/> }.into_any(),
+
+                    Err(e) => view! {
+                        <p>{format!("Error loading project: {}", e)}</p>
+                    }.into_any(),
+                })
+                .unwrap_or_else(|| view! { <p>"Loading..."</p> }.into_any())
+            }}
+        </Suspense>
+    </div>
+    }
+}
 
 #[component]
 fn Views() -> impl IntoView {
+    let owner_name = use_site_settings().get_untracked().owner_name;
 
-    let location=use_location();
+    // The page is in the query string, so it can be bookmarked and rendered on the server
+    let query = use_query_map();
 
     let blogs = Resource::new(
-        move || location.pathname.get(),
-        |_| async move {
+        move || query.with(|q| (q.get("older"), q.get("newer"))),
+        |(older, newer)| async move {
             {
-                get_blogs().await
+                get_blogs_page(older, newer, PAGE_SIZE).await
             }
 
         },
     );
 
     view! {
+    <PageMeta title="Views" description=format!("Views by {}.", owner_name)/>
     <div class="container mx-auto p-4 md:p-8 min-h-screen pt-20">
         <Suspense fallback=|| view! { <p>"Loading views..."</p> }>
             {move || {
-                blogs.read().clone().map(|res:Result<Vec<BlogRecord>, ServerFnError>| match res {
-                    Ok(vec) if !vec.is_empty() => view! {
+                blogs.read().clone().map(|res:Result<Page<BlogRecord>, ServerFnError>| match res {
+                    Ok(Page { items, older, newer }) if !items.is_empty() => view! {
                         <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-8">
                             <For
-                                each=move || vec.clone()
+                                each=move || items.clone()
                                 key=|blog| blog.id.clone()
-                                children=move |blog: BlogRecord| {
-                                    view! {
-                                        <div class="bg-charcoal-custom rounded-lg shadow-lg p-6">
-                                            <h2 class="text-xl font-bold mb-2">{blog.title}</h2>
-                                            <p>{blog.content}</p>
-                                        </div>
-                                    }
-                                }
+                                children=move |blog: BlogRecord| view! { <BlogCard blog/> }
                             />
                         </div>
+                        <PageNav path="/views".to_string() older=older newer=newer/>
                     }.into_any(),
 
                     Ok(_) => view! { <p>"No views found"</p> }.into_any(),
@@ -455,18 +1884,423 @@ fn Views() -> impl IntoView {
 
 }
 
+#[component]
+fn BlogCard(blog: BlogRecord) -> impl IntoView {
+    let href = format!("/views/{}", blog.slug);
+    view! {
+        <div class="bg-charcoal-custom rounded-lg shadow-lg p-6">
+            <h2 class="text-xl font-bold mb-2"><A href=href.clone()>{blog.title}</A></h2>
+            <PostDate created_at=blog.created_at updated_at=blog.updated_at/>
+            <TagList base="/views" tags=blog.tags/>
+            <p>{blog.excerpt}</p>
+            <p class="mt-4"><A href=href>"Read more"</A></p>
+        </div>
+    }
+}
+
+#[component]
+fn TaggedViews() -> impl IntoView {
+    let owner_name = use_site_settings().get_untracked().owner_name;
+    let params = use_params_map();
+    let query = use_query_map();
+
+    let tagged = Resource::new(
+        move || {
+            let tag = params.read().get("tag").unwrap_or_default();
+            query.with(|q| (tag, q.get("older"), q.get("newer")))
+        },
+        |(tag, older, newer)| async move { get_tagged_blogs_page(tag, older, newer, PAGE_SIZE).await },
+    );
+
+    view! {
+    <div class="container mx-auto p-4 md:p-8 min-h-screen pt-20">
+        <Suspense fallback=|| view! { <p>"Loading views..."</p> }>
+            {move || {
+                tagged.read().clone().map(|res:Result<Option<TaggedPage<BlogRecord>>, ServerFnError>| match res {
+                    Ok(Some(TaggedPage { tag, page: Page { items, older, newer } })) => {
+                        let listing = if items.is_empty() {
+                            view! { <p>"No views found"</p> }.into_any()
+                        } else {
+                            view! {
+                                <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-8">
+                                    <For
+                                        each=move || items.clone()
+                                        key=|blog| blog.id.clone()
+                                        children=move |blog: BlogRecord| view! { <BlogCard blog/> }
+                                    />
+                                </div>
+                            }.into_any()
+                        };
+                        view! {
+                            <PageMeta
+                                title=format!("Views tagged \"{}\"", tag.name)
+                                description=format!("Views by {} tagged \"{}\".", owner_name, tag.name)
+                            />
+                            <h1 class="text-3xl font-bold mb-8">{format!("Views tagged \"{}\"", tag.name)}</h1>
+                            {listing}
+                            <PageNav path=format!("/views/tag/{}", tag.slug) older=older newer=newer/>
+                        }.into_any()
+                    }
+
+                    Ok(None) => view! { <NotFound/> }.into_any(),
+
+                    Err(e) => view! {
+                        <p>{format!("Error loading views: {}", e)}</p>
+                    }.into_any(),
+                })
+                .unwrap_or_else(|| view! { <p>"Loading..."</p> }.into_any())
+            }}
+        </Suspense>
+    </div>
+    }
+}
+
+#[component]
+fn BlogPostPage() -> impl IntoView {
+    let params = use_params_map();
+
+    let blog = Resource::new(
+        move || params.read().get("slug").unwrap_or_default(),
+        |slug| async move { get_blog_by_slug(slug).await },
+    );
+
+    view! {
+    <div class="container mx-auto p-4 md:p-8 min-h-screen pt-20">
+        <Suspense fallback=|| view! { <p>"Loading view..."</p> }>
+            {move || {
+                blog.read().clone().map(|res:Result<Option<BlogRecord>, ServerFnError>| match res {
+                    Ok(Some(blog)) => view! {
+                        <PageMeta
+                            title=blog.title.clone()
+                            description=blog.excerpt
+                            image=blog.cover_image.or_else(|| Some(format!("/og/{}.png", blog.slug)))
+                            og_type="article"
+                        />
+                        <article class="max-w-3xl mx-auto">
+                            <h1 class="text-4xl font-bold mb-2">{blog.title}</h1>
+                            <div class="mb-8">
+                                <PostDate created_at=blog.created_at updated_at=blog.updated_at/>
+                                <TagList base="/views" tags=blog.tags/>
+                            </div>
+                            <div class="prose" inner_html=blog.content_html></div>
+                            <p class="mt-8"><A href="/views">"Back to all views"</A></p>
+                        </article>
+                    }.into_any(),
+
+                    Ok(None) => view! { <NotFound/> }.into_any(),
+
+                    Err(e) => view! {
+                        <p>{format!("Error loading view: {}", e)}</p>
+                    }.into_any(),
+                })
+                .unwrap_or_else(|| view! { <p>"Loading..."</p> }.into_any())
+            }}
+        </Suspense>
+    </div>
+    }
+}
+
+// Links to the pages either side of a paged listing at `path`.
+#[component]
+fn PageNav(path: String, older: Option<String>, newer: Option<String>) -> impl IntoView {
+    view! {
+        <nav class="flex justify-between mt-8">
+            <span>{newer.map(|cursor| view! { <A href=format!("{}?newer={}", path, cursor)>"← Newer"</A> })}</span>
+            <span>{older.map(|cursor| view! { <A href=format!("{}?older={}", path, cursor)>"Older →"</A> })}</span>
+        </nav>
+    }
+}
+
+// Links to the archive page of each tag, under `base` (`/views` or `/projects`).
+#[component]
+fn TagList(base: &'static str, tags: Vec<Tag>) -> impl IntoView {
+    view! {
+        <ul class="flex flex-wrap gap-2 mb-2 text-sm">
+            {tags
+                .into_iter()
+                .map(|tag| view! {
+                    <li class="border rounded px-2"><A href=format!("{}/tag/{}", base, tag.slug)>{format!("#{}", tag.name)}</A></li>
+                })
+                .collect_view()}
+        </ul>
+    }
+}
+
+// When a post was written, and last edited if that was on a later day.
+#[component]
+fn PostDate(created_at: OffsetDateTime, updated_at: OffsetDateTime) -> impl IntoView {
+    let edited = (updated_at.date() > created_at.date()).then(|| format!(" · Updated {}", format_date(updated_at)));
+
+    view! {
+        <p class="text-sm opacity-75 mb-2">
+            <time datetime=format_rfc3339(created_at)>{format_date(created_at)}</time>
+            {edited}
+        </p>
+    }
+}
+
+// The settings provided by `App`.
+fn use_site_settings() -> RwSignal<SiteSettings> {
+    use_context::<RwSignal<SiteSettings>>().unwrap_or_else(|| RwSignal::new(site_settings()))
+}
+
+// The site's public address: `SITE_URL` on the server, and wherever the browser already is.
+fn site_url() -> String {
+    #[cfg(feature = "ssr")]
+    {
+        crate::config::site_url()
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        window().location().origin().unwrap_or_default()
+    }
+}
+
+// Previews need absolute URLs, and images in content are usually linked from the site root.
+fn absolute_url(url: &str) -> String {
+    if url.starts_with("https://") || url.starts_with("http://") {
+        url.to_string()
+    } else {
+        format!("{}/{}", site_url(), url.trim_start_matches('/'))
+    }
+}
+
+// The title, description, canonical link, and OpenGraph and Twitter card tags of a page.
+// Crawlers only read what the server sends, so a page that has to load its record first
+// needs `SsrMode::Async`; streamed out, the tags would arrive after the `<head>`.
+#[component]
+fn PageMeta(
+    #[prop(into)] title: String,
+    #[prop(into)] description: String,
+    #[prop(default = None)] image: Option<String>,
+    // "article" for posts and projects
+    #[prop(default = "website")] og_type: &'static str,
+) -> impl IntoView {
+    let location = use_location();
+    let search = location.search.get_untracked();
+    let search = search.trim_start_matches('?');
+    let canonical = if search.is_empty() {
+        absolute_url(&location.pathname.get_untracked())
+    } else {
+        absolute_url(&format!("{}?{}", location.pathname.get_untracked(), search))
+    };
+    let settings = use_site_settings().get_untracked();
+    let site_title = settings.site_title.clone();
+    let full_title = if title == site_title { title.clone() } else { format!("{} | {}", title, site_title) };
+    let image = image.map(|image| absolute_url(&image));
+    let card = if image.is_some() { "summary_large_image" } else { "summary" };
+
+    view! {
+        <Title text=full_title/>
+        <Meta name="description" content=description.clone()/>
+        <Link rel="canonical" href=canonical.clone()/>
+        <Meta property="og:site_name" content=site_title/>
+        <Meta property="og:type" content=og_type/>
+        <Meta property="og:title" content=title.clone()/>
+        <Meta property="og:description" content=description.clone()/>
+        <Meta property="og:url" content=canonical/>
+        <Meta name="twitter:card" content=card/>
+        {settings.x_handle().map(|handle| view! { <Meta name="twitter:site" content=handle/> })}
+        <Meta name="twitter:title" content=title/>
+        <Meta name="twitter:description" content=description/>
+        {image.map(|image| view! {
+            <Meta property="og:image" content=image.clone()/>
+            <Meta name="twitter:image" content=image/>
+        })}
+    }
+}
+
+// Rendered by detail pages whose record doesn't exist. On the server it also sets the
+// response status, which only takes effect on routes rendered with `SsrMode::Async`.
+#[component]
+fn NotFound() -> impl IntoView {
+    #[cfg(feature = "ssr")]
+    if let Some(response) = use_context::<leptos_axum::ResponseOptions>() {
+        response.set_status(axum::http::StatusCode::NOT_FOUND);
+    }
+
+    view! { <p>"Page not found."</p> }
+}
+
+// In the header on every page; submitting it navigates to `/search?q=...`.
+#[component]
+fn SearchBox() -> impl IntoView {
+    let query = use_query_map();
+
+    view! {
+        <Form action="/search">
+            <input
+                type="search"
+                name="q"
+                prop:value=move || query.read().get("q").unwrap_or_default()
+                placeholder="Search"
+                aria-label="Search posts and projects"
+                class="w-32 md:w-48 rounded py-1 px-2 text-base bg-charcoal-custom text-navy-blue-custom"
+            />
+        </Form>
+    }
+}
+
+#[component]
+fn SearchPage() -> impl IntoView {
+    let owner_name = use_site_settings().get_untracked().owner_name;
+    let query = use_query_map();
+
+    let results = Resource::new(
+        move || query.read().get("q").unwrap_or_default(),
+        |q| async move { search_content(q).await },
+    );
+
+    view! {
+    <PageMeta title="Search" description=format!("Search posts and projects by {}.", owner_name)/>
+    <div class="container mx-auto p-4 md:p-8 min-h-screen pt-20">
+        <Suspense fallback=|| view! { <p>"Searching..."</p> }>
+            {move || {
+                let q = query.read().get("q").unwrap_or_default();
+                results.read().clone().map(|res:Result<Vec<SearchResult>, ServerFnError>| match res {
+                    _ if q.trim().is_empty() => view! { <p>"Type something in the search box to search posts and projects."</p> }.into_any(),
+
+                    Ok(results) if !results.is_empty() => view! {
+                        <h1 class="text-3xl font-bold mb-8">{format!("Results for \"{}\"", q.trim())}</h1>
+                        <ul class="max-w-3xl space-y-6">
+                            {results
+                                .into_iter()
+                                .map(|result| view! {
+                                    <li class="bg-charcoal-custom rounded-lg shadow-lg p-6">
+                                        <span class="inline-block mb-2 px-2 py-1 rounded text-sm font-bold border">{result.kind.label()}</span>
+                                        <h2 class="text-xl font-bold mb-2"><A href=result.href><span inner_html=result.title_html></span></A></h2>
+                                        <p inner_html=result.snippet_html></p>
+                                    </li>
+                                })
+                                .collect_view()}
+                        </ul>
+                    }.into_any(),
+
+                    Ok(_) => view! { <p>{format!("Nothing found for \"{}\"", q.trim())}</p> }.into_any(),
+
+                    Err(e) => view! {
+                        <p>{format!("Error searching: {}", e)}</p>
+                    }.into_any(),
+                })
+                .unwrap_or_else(|| view! { <p>"Searching..."</p> }.into_any())
+            }}
+        </Suspense>
+    </div>
+    }
+}
+
 #[component]
 fn Contacts() -> impl IntoView {
+    let SiteSettings { owner_name, email, profile_links, .. } = use_site_settings().get_untracked();
     view! {
+        <PageMeta title="Contacts" description=format!("How to get in touch with {}.", owner_name)/>
         <div class="min-h-screen flex flex-col justify-center items-center bg-sandy-beige text-navy-blue-custom">
             <h1>"Contact Me!"</h1>
-            <p>"Email- shilohantonyjohn@email.com"</p>
-            <p>"X- @ShilohAJohn"</p>
-            <p>"Github- ShilohAntonyJohn"</p>
+            {(!email.is_empty()).then(|| view! {
+                <p>"Email- "<a href=format!("mailto:{}", email) rel="me">{email.clone()}</a></p>
+            })}
+            {profile_links
+                .into_iter()
+                .map(|link| view! {
+                    <p>{link.label.clone()}"- "<a href=link.url.clone() rel="me">{link.display_url().to_string()}</a></p>
+                })
+                .collect_view()}
+            <ContactForm/>
         </div>
     }
 }
 
+// Server function errors arrive as "error running server function: <reason>"; only the reason
+// is meant for people.
+fn error_reason(error: &ServerFnError) -> String {
+    match error {
+        ServerFnError::ServerError(reason) => reason.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[component]
+fn ContactForm() -> impl IntoView {
+    // Fetched with the page, so it records when the form was shown
+    let form_token = Resource::new(|| (), |_| async move { get_contact_form_token().await });
+    let submit_action = ServerAction::<SubmitContactMessage>::new();
+    let name = RwSignal::new(String::new());
+    let email = RwSignal::new(String::new());
+    let message = RwSignal::new(String::new());
+    let website = RwSignal::new(String::new());
+    let (status, set_status) = signal("".to_string());
+
+    Effect::new(move |_| match submit_action.value().get() {
+        Some(Ok(())) => {
+            set_status.set("Thanks! Your message has been sent.".to_string());
+            name.set(String::new());
+            email.set(String::new());
+            message.set(String::new());
+        }
+        Some(Err(e)) => set_status.set(error_reason(&e)),
+        None => {}
+    });
+
+    let on_submit = move |ev: ev::SubmitEvent| {
+        ev.prevent_default();
+        let Some(Ok(token)) = form_token.get_untracked() else {
+            set_status.set("The form is still loading, please try again in a moment.".to_string());
+            return;
+        };
+        submit_action.dispatch(SubmitContactMessage {
+            name: name.get_untracked(),
+            email: email.get_untracked(),
+            message: message.get_untracked(),
+            website: website.get_untracked(),
+            form_token: token,
+        });
+    };
+
+    let input_class = "shadow appearance-none border rounded w-full py-2 px-3 text-navy-blue-custom-700 leading-tight focus:outline-none focus:shadow-outline focus:border-blue-500";
+    view! {
+        <form on:submit=on_submit class="w-full max-w-lg mt-8 p-6">
+            <h2 class="text-2xl font-semibold mb-4">"Send a Message"</h2>
+            <Suspense>
+                {move || form_token.get().and_then(Result::ok).map(|token| view! {
+                    <input type="hidden" name="form_token" value=token/>
+                })}
+            </Suspense>
+            <div class="mb-4">
+                <label for="contact-name" class="block text-sm font-bold mb-2">"Name:"</label>
+                <input id="contact-name" type="text" required maxlength="100" bind:value=name class=input_class/>
+            </div>
+            <div class="mb-4">
+                <label for="contact-email" class="block text-sm font-bold mb-2">"Email:"</label>
+                <input id="contact-email" type="email" required maxlength="254" bind:value=email class=input_class/>
+            </div>
+            // The honeypot: out of sight and out of the tab order, so only bots fill it in
+            <div class="absolute -left-[9999px]" aria-hidden="true">
+                <label for="contact-website">"Leave this empty:"</label>
+                <input id="contact-website" type="text" tabindex="-1" autocomplete="off" bind:value=website/>
+            </div>
+            <div class="mb-4">
+                <label for="contact-message" class="block text-sm font-bold mb-2">"Message:"</label>
+                <textarea
+                    id="contact-message"
+                    required
+                    maxlength="5000"
+                    prop:value=message
+                    on:input=move |ev| message.set(event_target_value(&ev))
+                    class=format!("{} h-32 resize-y", input_class)
+                ></textarea>
+            </div>
+            <button
+                type="submit"
+                disabled=move || submit_action.pending().get()
+                class="bg-navy-blue-custom text-sandy-beige font-bold py-2 px-4 rounded hover:bg-navy-blue-custom-dark focus:outline-none focus:shadow-outline disabled:opacity-50"
+            >
+                "Send"
+            </button>
+            <p class="mt-4 text-sm">{status}</p>
+        </form>
+    }
+}
+
 
 #[component]
 fn LoginPage() -> impl IntoView {
@@ -513,6 +2347,8 @@ fn LoginPage() -> impl IntoView {
                                     navigate_for_submit("/adminpanel", Default::default());
                                 } else if response.status() == 401 {
                                     message.set("Invalid email or password.".to_string());
+                                } else if response.status() == 429 {
+                                    message.set("Too many login attempts. Please try again later.".to_string());
                                 } else {
                                     message.set(format!("Login failed: Status {}", response.status()));
                                 }
@@ -553,10 +2389,209 @@ fn LoginPage() -> impl IntoView {
                 </button>
             </form>
         <p >{message}</p>
+            <p class="mt-4 text-sm"><A href="/login/forgot">"Forgot your password?"</A></p>
+        </div>
+    }
+}
+
+#[component]
+fn ForgotPasswordPage() -> impl IntoView {
+    let request_action = ServerAction::<RequestPasswordReset>::new();
+    let email = RwSignal::new(String::new());
+    let (status, set_status) = signal("".to_string());
+
+    Effect::new(move |_| match request_action.value().get() {
+        Some(Ok(())) => set_status.set(
+            "If that address has an account, a link to reset its password is on its way. It works for an hour."
+                .to_string(),
+        ),
+        Some(Err(e)) => set_status.set(error_reason(&e)),
+        None => {}
+    });
+
+    let on_submit = move |ev: ev::SubmitEvent| {
+        ev.prevent_default();
+        request_action.dispatch(RequestPasswordReset { email: email.get_untracked() });
+    };
+
+    view! {
+        <div class="h-full w-full flex flex-col justify-center items-center bg-sandy-beige text-navy-blue-custom">
+            <h1>"Forgot your password?"</h1>
+            <p class="mb-4 text-sm">"Enter your email address and you'll be sent a link to choose a new one."</p>
+            <form on:submit=on_submit class="flex flex-col gap-4">
+                <input
+                    type="email"
+                    placeholder="Email"
+                    required
+                    bind:value=email
+                    class="p-2 border border-gray-300 rounded"
+                />
+                <button
+                    type="submit"
+                    disabled=move || request_action.pending().get()
+                    class="bg-navy-blue-custom text-sandy-beige p-2 rounded disabled:opacity-50"
+                >
+                    "Send reset link"
+                </button>
+            </form>
+            <p>{status}</p>
+            <p class="mt-4 text-sm"><A href="/login">"Back to login"</A></p>
+        </div>
+    }
+}
+
+#[component]
+fn ResetPasswordPage() -> impl IntoView {
+    let params = use_params_map();
+    let reset_action = ServerAction::<ResetPassword>::new();
+    let password = RwSignal::new(String::new());
+    let confirmation = RwSignal::new(String::new());
+    let (status, set_status) = signal("".to_string());
+    let done = move || matches!(reset_action.value().get(), Some(Ok(())));
+
+    Effect::new(move |_| match reset_action.value().get() {
+        Some(Ok(())) => {
+            set_status.set("Your password has been changed and every session signed out. You can log in now.".to_string());
+            password.set(String::new());
+            confirmation.set(String::new());
+        }
+        Some(Err(e)) => set_status.set(error_reason(&e)),
+        None => {}
+    });
+
+    let on_submit = move |ev: ev::SubmitEvent| {
+        ev.prevent_default();
+        if password.get_untracked() != confirmation.get_untracked() {
+            set_status.set("The two passwords don't match.".to_string());
+            return;
+        }
+        reset_action.dispatch(ResetPassword {
+            token: params.with_untracked(|params| params.get("token").unwrap_or_default()),
+            password: password.get_untracked(),
+        });
+    };
+
+    view! {
+        // The token is in the address, so it mustn't leak to other sites through `Referer`
+        <Meta name="referrer" content="no-referrer"/>
+        <div class="h-full w-full flex flex-col justify-center items-center bg-sandy-beige text-navy-blue-custom">
+            <h1>"Choose a new password"</h1>
+            <Show when=move || !done()>
+                <form on:submit=on_submit class="flex flex-col gap-4">
+                    <input
+                        type="password"
+                        placeholder="New password"
+                        required
+                        autocomplete="new-password"
+                        bind:value=password
+                        class="p-2 border border-gray-300 rounded"
+                    />
+                    <input
+                        type="password"
+                        placeholder="New password, again"
+                        required
+                        autocomplete="new-password"
+                        bind:value=confirmation
+                        class="p-2 border border-gray-300 rounded"
+                    />
+                    <button
+                        type="submit"
+                        disabled=move || reset_action.pending().get()
+                        class="bg-navy-blue-custom text-sandy-beige p-2 rounded disabled:opacity-50"
+                    >
+                        "Reset password"
+                    </button>
+                </form>
+            </Show>
+            <p>{status}</p>
+            <p class="mt-4 text-sm"><A href="/login">"Back to login"</A></p>
+        </div>
+    }
+}
+
+#[component]
+fn VerifyEmailPage() -> impl IntoView {
+    let params = use_params_map();
+    let verify_action = ServerAction::<VerifyEmail>::new();
+    let (status, set_status) = signal("".to_string());
+    let done = move || matches!(verify_action.value().get(), Some(Ok(())));
+
+    Effect::new(move |_| match verify_action.value().get() {
+        Some(Ok(())) => set_status.set("Thanks, your email address is confirmed.".to_string()),
+        Some(Err(e)) => set_status.set(error_reason(&e)),
+        None => {}
+    });
+
+    // Confirmed with a click rather than on load, so mail scanners that open links don't use it up
+    let on_submit = move |ev: ev::SubmitEvent| {
+        ev.prevent_default();
+        verify_action.dispatch(VerifyEmail {
+            token: params.with_untracked(|params| params.get("token").unwrap_or_default()),
+        });
+    };
+
+    view! {
+        // The token is in the address, so it mustn't leak to other sites through `Referer`
+        <Meta name="referrer" content="no-referrer"/>
+        <div class="h-full w-full flex flex-col justify-center items-center bg-sandy-beige text-navy-blue-custom">
+            <h1>"Confirm your email address"</h1>
+            <Show when=move || !done()>
+                <form on:submit=on_submit class="flex flex-col gap-4">
+                    <button
+                        type="submit"
+                        disabled=move || verify_action.pending().get()
+                        class="bg-navy-blue-custom text-sandy-beige p-2 rounded disabled:opacity-50"
+                    >
+                        "Confirm"
+                    </button>
+                </form>
+            </Show>
+            <p>{status}</p>
+            <p class="mt-4 text-sm"><A href="/login">"Back to login"</A></p>
         </div>
     }
 }
 
+// The admin panel's tag inputs hold a comma-separated list of tag names.
+fn split_tags(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn join_tags(tags: &[Tag]) -> String {
+    tags.iter().map(|tag| tag.name.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+// The Markdown for a library image, with the file name (sans extension) as its alt text.
+fn image_markdown(media: &MediaRecord) -> String {
+    let name = media.original_name.rsplit_once('.').map_or(media.original_name.as_str(), |(stem, _)| stem);
+    let alt: String = name.chars().filter(|c| !matches!(c, '[' | ']')).collect();
+    format!("![{}]({})", alt, media.url)
+}
+
+// Adds a block of Markdown to the end of the text, separated by a blank line.
+fn append_block(text: &mut String, block: &str) {
+    let trimmed = text.trim_end().len();
+    text.truncate(trimmed);
+    if !text.is_empty() {
+        text.push_str("\n\n");
+    }
+    text.push_str(block);
+    text.push('\n');
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{} KB", bytes.div_ceil(1024))
+    }
+}
+
 #[component]
 fn AdminPanel() -> impl IntoView {
     let (blog_title, set_blog_title) = signal("".to_string()); // Renamed for clarity
@@ -564,6 +2599,15 @@ fn AdminPanel() -> impl IntoView {
     let (project_title, set_project_title) = signal("".to_string()); // New signal for project title
     let (project_content, set_project_content) = signal("".to_string()); // New signal for project content
     let (project_link, set_project_link) = signal("".to_string()); // New signal for project link
+    let (project_description, set_project_description) = signal("".to_string()); // Long-form case study
+    // `datetime-local` values in UTC; empty publishes straight away
+    let (blog_publish_at, set_blog_publish_at) = signal("".to_string());
+    let (project_publish_at, set_project_publish_at) = signal("".to_string());
+    // Comma-separated tag names
+    let (blog_tags, set_blog_tags) = signal("".to_string());
+    let (project_tags, set_project_tags) = signal("".to_string());
+    // Image URL, empty for none
+    let (project_cover_image, set_project_cover_image) = signal("".to_string());
     let (publish_status, set_publish_status) = signal("".to_string());
 
 
@@ -575,28 +2619,213 @@ fn AdminPanel() -> impl IntoView {
     let delete_project_action = ServerAction::<DeleteProject>::new();
     let delete_blog_action = ServerAction::<DeleteBlog>::new();
 
+    // --- Edit mode: the id of the record loaded into the form, if any ---
+    let (editing_blog_id, set_editing_blog_id) = signal(None::<String>);
+    let (editing_project_id, set_editing_project_id) = signal(None::<String>);
+    let update_blog_action = ServerAction::<UpdateBlog>::new();
+    let update_project_action = ServerAction::<UpdateProject>::new();
+
+    Effect::new(move |_| match update_blog_action.value().get() {
+        Some(Ok(())) => {
+            set_publish_status.set("Blog post updated successfully!".to_string());
+            set_editing_blog_id.set(None);
+            set_blog_title.set("".to_string()); // Clear fields
+            set_blog_content.set("".to_string());
+            set_blog_publish_at.set("".to_string());
+            set_blog_tags.set("".to_string());
+        }
+        Some(Err(e)) => set_publish_status.set(format!("Failed to update blog post: {}", e)),
+        None => {}
+    });
+    Effect::new(move |_| match update_project_action.value().get() {
+        Some(Ok(())) => {
+            set_publish_status.set("Project updated successfully!".to_string());
+            set_editing_project_id.set(None);
+            set_project_title.set("".to_string()); // Clear fields
+            set_project_content.set("".to_string());
+            set_project_link.set("".to_string());
+            set_project_description.set("".to_string());
+            set_project_publish_at.set("".to_string());
+            set_project_tags.set("".to_string());
+            set_project_cover_image.set("".to_string());
+        }
+        Some(Err(e)) => set_publish_status.set(format!("Failed to update project: {}", e)),
+        None => {}
+    });
+
+    // Publishing with a publish time schedules the record instead; drafts keep the time for later.
+    let status_for = |publish_at: &Option<OffsetDateTime>| match publish_at {
+        Some(_) => PublishStatus::Scheduled,
+        None => PublishStatus::Published,
+    };
+
+    let save_blog = move |draft: bool| {
+        let Some(id) = editing_blog_id.get_untracked() else { return };
+        let title = blog_title.get_untracked();
+        let content = blog_content.get_untracked();
+        if title.is_empty() || content.is_empty() {
+            set_publish_status.set("Blog title or content cannot be empty.".to_string());
+            return;
+        }
+        let publish_at = parse_datetime_local(&blog_publish_at.get_untracked());
+        let status = if draft { PublishStatus::Draft } else { status_for(&publish_at) };
+        let publish_at = publish_at.map(format_rfc3339);
+        let tags = split_tags(&blog_tags.get_untracked());
+        update_blog_action.dispatch(UpdateBlog { id, blog: BlogPost { title, content, status, publish_at, tags } });
+    };
+    let on_cancel_blog_edit = move |_| {
+        set_editing_blog_id.set(None);
+        set_blog_title.set("".to_string());
+        set_blog_content.set("".to_string());
+        set_blog_publish_at.set("".to_string());
+        set_blog_tags.set("".to_string());
+    };
+    let save_project = move |draft: bool| {
+        let Some(id) = editing_project_id.get_untracked() else { return };
+        let title = project_title.get_untracked();
+        let content = project_content.get_untracked();
+        let link = project_link.get_untracked();
+        let description = project_description.get_untracked();
+        if title.is_empty() || content.is_empty() {
+            set_publish_status.set("Project title or content cannot be empty.".to_string());
+            return;
+        }
+        let publish_at = parse_datetime_local(&project_publish_at.get_untracked());
+        let status = if draft { PublishStatus::Draft } else { status_for(&publish_at) };
+        let publish_at = publish_at.map(format_rfc3339);
+        let tags = split_tags(&project_tags.get_untracked());
+        let cover_image = Some(project_cover_image.get_untracked()).filter(|url| !url.is_empty());
+        update_project_action.dispatch(UpdateProject {
+            id,
+            project: Project { title, content, link, description, status, publish_at, tags, cover_image },
+        });
+    };
+    let on_cancel_project_edit = move |_| {
+        set_editing_project_id.set(None);
+        set_project_title.set("".to_string());
+        set_project_content.set("".to_string());
+        set_project_link.set("".to_string());
+        set_project_description.set("".to_string());
+        set_project_publish_at.set("".to_string());
+        set_project_tags.set("".to_string());
+        set_project_cover_image.set("".to_string());
+    };
+
+    // --- Logging out ---
+    let logout_everywhere_action = ServerAction::<LogoutEverywhere>::new();
+    let navigate = use_navigate();
+    let navigate_for_logout_everywhere = navigate.clone();
+    Effect::new(move |_| {
+        if let Some(Ok(())) = logout_everywhere_action.value().get() {
+            navigate_for_logout_everywhere("/login", Default::default());
+        }
+    });
+    let on_logout = move |_| {
+        #[cfg(feature = "hydrate")]
+        let navigate_for_logout = navigate.clone();
+        spawn_local(async move {
+            #[cfg(feature = "hydrate")]
+            {
+                use gloo_net::http::Request;
+                use crate::csrf::{csrf_token, CSRF_HEADER};
+
+                let request = Request::post("/api/admin/logout").header(CSRF_HEADER, &csrf_token().unwrap_or_default());
+                match request.send().await {
+                    // A 401 means the session had already ended
+                    Ok(response) if response.ok() || response.status() == 401 => {
+                        navigate_for_logout("/login", Default::default());
+                    }
+                    Ok(response) => {
+                        set_publish_status.set(format!("Failed to log out: Status {}", response.status()));
+                    }
+                    Err(e) => {
+                        set_publish_status.set(format!("Error sending logout request: {}", e));
+                    }
+                }
+            }
+        });
+    };
+
+
+    // --- Media library ---
+    let media_version = RwSignal::new(0);
+    let media = Resource::new(move || media_version.get(), |_| async move { get_media().await });
+    let file_input = NodeRef::<leptos::html::Input>::new();
+    let upload_status = RwSignal::new(String::new());
+    let on_upload = move |_| {
+        #[cfg(feature = "hydrate")]
+        {
+            let Some(input) = file_input.get() else { return };
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                upload_status.set("Choose an image to upload first.".to_string());
+                return;
+            };
+            upload_status.set(format!("Uploading {}...", file.name()));
+            spawn_local(async move {
+                use gloo_net::http::Request;
+                use crate::csrf::{csrf_token, CSRF_HEADER};
+
+                let form = match web_sys::FormData::new() {
+                    Ok(form) => form,
+                    Err(_) => {
+                        upload_status.set("Error building upload request.".to_string());
+                        return;
+                    }
+                };
+                if form.append_with_blob_and_filename("file", &file, &file.name()).is_err() {
+                    upload_status.set("Error building upload request.".to_string());
+                    return;
+                }
+                // No Content-Type here: the browser sets it, with the multipart boundary
+                let request = Request::post("/api/admin/upload")
+                    .header(CSRF_HEADER, &csrf_token().unwrap_or_default())
+                    .body(form);
+
+                match request {
+                    Ok(req_builder) => match req_builder.send().await {
+                        Ok(response) if response.status() == 201 => {
+                            upload_status.set(format!("Uploaded {}.", file.name()));
+                            input.set_value("");
+                            media_version.update(|version| *version += 1);
+                        }
+                        Ok(response) if response.status() == 401 => {
+                            upload_status.set("Unauthorized. Please login again.".to_string());
+                        }
+                        Ok(response) => {
+                            let reason = response.text().await.unwrap_or_default();
+                            upload_status.set(format!("Failed to upload: {}", reason));
+                        }
+                        Err(e) => upload_status.set(format!("Error sending upload request: {}", e)),
+                    },
+                    Err(e) => upload_status.set(format!("Error building upload request: {}", e)),
+                }
+            });
+        }
+    };
 
     let location=use_location();
     let location1=location.clone();
     let projects = Resource::new(
-        move || (delete_project_action.version().get(), location.pathname.get()),
+        move || (delete_project_action.version().get(), update_project_action.version().get(), location.pathname.get()),
         |_| async move {
             {
-                get_projects().await
+                get_all_projects().await
             }
 
         },
     );
     let blogs = Resource::new(
-        move || (delete_blog_action.version().get(), location1.pathname.get()),
+        move || (delete_blog_action.version().get(), update_blog_action.version().get(), location1.pathname.get()),
         |_| async move {
             {
-                get_blogs().await
+                get_all_blogs().await
             }
 
         },
     );
-    let on_publish_blog = move |_| {
+    // Only sends anything from the browser, hence the unused `draft` on the server
+    #[cfg_attr(not(feature = "hydrate"), allow(unused_variables))]
+    let publish_blog = move |draft: bool| {
         let current_title = blog_title.get_untracked();
         let current_content = blog_content.get_untracked();
 
@@ -609,14 +2838,22 @@ fn AdminPanel() -> impl IntoView {
             #[cfg(feature = "hydrate")]
             {
                 use gloo_net::http::Request;
+                use crate::csrf::{csrf_token, CSRF_HEADER};
+                let publish_at = parse_datetime_local(&blog_publish_at.get_untracked());
+                let status = if draft { PublishStatus::Draft } else { status_for(&publish_at) };
+                let publish_at = publish_at.map(format_rfc3339);
                 let blog_post_data = BlogPost {
                     title: current_title,
                     content: current_content,
+                    status,
+                    publish_at,
+                    tags: split_tags(&blog_tags.get_untracked()),
                 };
 
                 let request_body = serde_json::to_string(&blog_post_data).expect("Failed to serialize blog post");
 
                 let request = Request::post("/api/publish-blog")
+                    .header(CSRF_HEADER, &csrf_token().unwrap_or_default())
                     .header("Content-Type", "application/json")
                     .body(request_body); // Use .body() for raw string
 
@@ -625,9 +2862,15 @@ fn AdminPanel() -> impl IntoView {
                         match req_builder.send().await {
                             Ok(response) => {
                                 if response.status() == 201 { // 201 Created is typical for successful creation
-                                    set_publish_status.set("Blog post published successfully!".to_string());
+                                    set_publish_status.set(match status {
+                                        PublishStatus::Draft => "Blog post saved as a draft.".to_string(),
+                                        PublishStatus::Scheduled => "Blog post scheduled successfully!".to_string(),
+                                        PublishStatus::Published => "Blog post published successfully!".to_string(),
+                                    });
                                     set_blog_title.set("".to_string()); // Clear fields
                                     set_blog_content.set("".to_string());
+                                    set_blog_publish_at.set("".to_string());
+                                    set_blog_tags.set("".to_string());
                                 } else if response.status() == 401 {
                                     set_publish_status.set("Unauthorized. Please login again.".to_string());
                                 } else {
@@ -647,7 +2890,9 @@ fn AdminPanel() -> impl IntoView {
         });
     };
 
-    let on_publish_project = move |_| {
+    // Only sends anything from the browser, hence the unused `draft` on the server
+    #[cfg_attr(not(feature = "hydrate"), allow(unused_variables))]
+    let publish_project = move |draft: bool| {
         let current_title = project_title.get_untracked();
         let current_content = project_content.get_untracked();
         let current_link = project_link.get_untracked();
@@ -661,15 +2906,25 @@ fn AdminPanel() -> impl IntoView {
             #[cfg(feature = "hydrate")]
             {
                 use gloo_net::http::Request;
+                use crate::csrf::{csrf_token, CSRF_HEADER};
+                let publish_at = parse_datetime_local(&project_publish_at.get_untracked());
+                let status = if draft { PublishStatus::Draft } else { status_for(&publish_at) };
+                let publish_at = publish_at.map(format_rfc3339);
                 let project_data = Project {
                     title: current_title,
                     content: current_content,
                     link: current_link,
+                    description: project_description.get_untracked(),
+                    status,
+                    publish_at,
+                    tags: split_tags(&project_tags.get_untracked()),
+                    cover_image: Some(project_cover_image.get_untracked()).filter(|url| !url.is_empty()),
                 };
 
                 let request_body = serde_json::to_string(&project_data).expect("Failed to serialize project");
 
                 let request = Request::post("/api/publish-project")
+                    .header(CSRF_HEADER, &csrf_token().unwrap_or_default())
                     .header("Content-Type", "application/json")
                     .body(request_body);
 
@@ -678,10 +2933,18 @@ fn AdminPanel() -> impl IntoView {
                         match req_builder.send().await {
                             Ok(response) => {
                                 if response.status() == 201 {
-                                    set_publish_status.set("Project published successfully!".to_string());
+                                    set_publish_status.set(match status {
+                                        PublishStatus::Draft => "Project saved as a draft.".to_string(),
+                                        PublishStatus::Scheduled => "Project scheduled successfully!".to_string(),
+                                        PublishStatus::Published => "Project published successfully!".to_string(),
+                                    });
                                     set_project_title.set("".to_string()); // Clear fields
                                     set_project_content.set("".to_string());
                                     set_project_link.set("".to_string());
+                                    set_project_description.set("".to_string());
+                                    set_project_publish_at.set("".to_string());
+                                    set_project_tags.set("".to_string());
+                                    set_project_cover_image.set("".to_string());
                                 } else if response.status() == 401 {
                                     set_publish_status.set("Unauthorized. Please login again.".to_string());
                                 } else {
@@ -704,9 +2967,25 @@ fn AdminPanel() -> impl IntoView {
     view! {
         <div class="container mx-auto p-4 md:p-8 min-h-screen text-navy-blue-custom-800">
             <h1 class="text-4xl font-bold mb-8 text-center text-navy-blue-custom-700">"Admin Dashboard"</h1>
+            <div class="flex justify-end gap-2 mb-8">
+                <button
+                    on:click=on_logout
+                    class="bg-navy-blue-custom text-sandy-beige font-bold py-2 px-4 rounded hover:bg-navy-blue-custom-dark focus:outline-none focus:shadow-outline"
+                >
+                    "Log Out"
+                </button>
+                <button
+                    on:click=move |_| { logout_everywhere_action.dispatch(LogoutEverywhere {}); }
+                    class="bg-red-600 text-white font-bold py-2 px-4 rounded hover:bg-red-700"
+                >
+                    "Log Out Everywhere"
+                </button>
+            </div>
 
             <section class="p-6 rounded-lg shadow-lg mb-8">
-                <h2 class="text-2xl font-semibold mb-4 text-navy-blue-custom-600">"Publish Blog Post"</h2>
+                <h2 class="text-2xl font-semibold mb-4 text-navy-blue-custom-600">
+                    {move || if editing_blog_id.get().is_some() { "Edit Blog Post" } else { "Publish Blog Post" }}
+                </h2>
                 <div class="mb-4">
                     <label for="blog-title" class="block text-navy-blue-custom-700 text-sm font-bold mb-2">"Title:"</label>
                     <input
@@ -725,19 +3004,76 @@ fn AdminPanel() -> impl IntoView {
                         prop:value=blog_content
                         on:input=move |ev| set_blog_content.set(event_target_value(&ev))
                         class="shadow appearance-none border rounded w-full py-2 px-3 text-navy-blue-custom-700 leading-tight focus:outline-none focus:shadow-outline focus:border-blue-500 h-32 resize-y" // Adjusted height
-                        placeholder="Write your blog post content here..."
+                        placeholder="Write your blog post content here, in Markdown..."
                     ></textarea>
                 </div>
-                <button
-                    on:click=on_publish_blog
-                    class="bg-navy-blue-custom text-sandy-beige font-bold py-2 px-4 rounded hover:bg-navy-blue-custom-dark focus:outline-none focus:shadow-outline"
+                <div class="mb-4">
+                    <label for="blog-tags" class="block text-navy-blue-custom-700 text-sm font-bold mb-2">"Tags (comma separated):"</label>
+                    <input
+                        id="blog-tags"
+                        type="text"
+                        prop:value=blog_tags
+                        on:input=move |ev| set_blog_tags.set(event_target_value(&ev))
+                        class="shadow appearance-none border rounded w-full py-2 px-3 text-navy-blue-custom-700 leading-tight focus:outline-none focus:shadow-outline focus:border-blue-500"
+                        placeholder="rust, leptos"
+                    />
+                </div>
+                <div class="mb-6">
+                    <label for="blog-publish-at" class="block text-navy-blue-custom-700 text-sm font-bold mb-2">"Publish At (UTC, optional):"</label>
+                    <input
+                        id="blog-publish-at"
+                        type="datetime-local"
+                        prop:value=blog_publish_at
+                        on:input=move |ev| set_blog_publish_at.set(event_target_value(&ev))
+                        class="shadow appearance-none border rounded py-2 px-3 text-navy-blue-custom-700 leading-tight focus:outline-none focus:shadow-outline focus:border-blue-500"
+                    />
+                </div>
+                <Show
+                    when=move || editing_blog_id.get().is_some()
+                    fallback=move || view! {
+                        <div class="flex gap-2">
+                            <button
+                                on:click=move |_| publish_blog(false)
+                                class="bg-navy-blue-custom text-sandy-beige font-bold py-2 px-4 rounded hover:bg-navy-blue-custom-dark focus:outline-none focus:shadow-outline"
+                            >
+                                "Publish Blog Post"
+                            </button>
+                            <button
+                                on:click=move |_| publish_blog(true)
+                                class="border rounded font-bold py-2 px-4"
+                            >
+                                "Save as Draft"
+                            </button>
+                        </div>
+                    }
                 >
-                    "Publish Blog Post"
-                </button>
+                    <div class="flex gap-2">
+                        <button
+                            on:click=move |_| save_blog(false)
+                            class="bg-navy-blue-custom text-sandy-beige font-bold py-2 px-4 rounded hover:bg-navy-blue-custom-dark focus:outline-none focus:shadow-outline"
+                        >
+                            "Save and Publish"
+                        </button>
+                        <button
+                            on:click=move |_| save_blog(true)
+                            class="border rounded font-bold py-2 px-4"
+                        >
+                            "Save as Draft"
+                        </button>
+                        <button
+                            on:click=on_cancel_blog_edit
+                            class="border rounded font-bold py-2 px-4"
+                        >
+                            "Cancel"
+                        </button>
+                    </div>
+                </Show>
             </section>
 
             <section class="p-6 rounded-lg shadow-lg mb-8">
-                <h2 class="text-2xl font-semibold mb-4 text-navy-blue-custom-600">"Publish Project"</h2>
+                <h2 class="text-2xl font-semibold mb-4 text-navy-blue-custom-600">
+                    {move || if editing_project_id.get().is_some() { "Edit Project" } else { "Publish Project" }}
+                </h2>
                 <div class="mb-4">
                     <label for="project-title" class="block text-navy-blue-custom-700 text-sm font-bold mb-2">"Title:"</label>
                     <input
@@ -770,15 +3106,172 @@ fn AdminPanel() -> impl IntoView {
                         placeholder="Enter your project's link"
                     />
                 </div>
-                <button
-                    on:click=on_publish_project
-                    class="bg-navy-blue-custom text-sandy-beige font-bold py-2 px-4 rounded hover:bg-navy-blue-custom-dark focus:outline-none focus:shadow-outline"
+                <div class="mb-6">
+                    <label for="project-description" class="block text-navy-blue-custom-700 text-sm font-bold mb-2">"Case Study:"</label>
+                    <textarea
+                        id="project-description"
+                        prop:value=project_description
+                        on:input=move |ev| set_project_description.set(event_target_value(&ev))
+                        class="shadow appearance-none border rounded w-full py-2 px-3 text-navy-blue-custom-700 leading-tight focus:outline-none focus:shadow-outline focus:border-blue-500 h-64 resize-y"
+                        placeholder="Write the long-form write-up for the project's own page..."
+                    ></textarea>
+                </div>
+                <div class="mb-6">
+                    <label for="project-cover-image" class="block text-navy-blue-custom-700 text-sm font-bold mb-2">"Cover Image (pick one from the media library below):"</label>
+                    <div class="flex gap-2 items-center">
+                        <input
+                            id="project-cover-image"
+                            type="text"
+                            prop:value=project_cover_image
+                            on:input=move |ev| set_project_cover_image.set(event_target_value(&ev))
+                            class="shadow appearance-none border rounded w-full py-2 px-3 text-navy-blue-custom-700 leading-tight focus:outline-none focus:shadow-outline focus:border-blue-500"
+                            placeholder="No cover image"
+                        />
+                        <button
+                            on:click=move |_| set_project_cover_image.set("".to_string())
+                            class="border rounded font-bold py-2 px-4"
+                        >
+                            "Clear"
+                        </button>
+                    </div>
+                    <Show when=move || !project_cover_image.get().is_empty()>
+                        <img src=project_cover_image alt="Cover image" class="mt-2 max-h-32 rounded"/>
+                    </Show>
+                </div>
+                <div class="mb-4">
+                    <label for="project-tags" class="block text-navy-blue-custom-700 text-sm font-bold mb-2">"Tags (comma separated):"</label>
+                    <input
+                        id="project-tags"
+                        type="text"
+                        prop:value=project_tags
+                        on:input=move |ev| set_project_tags.set(event_target_value(&ev))
+                        class="shadow appearance-none border rounded w-full py-2 px-3 text-navy-blue-custom-700 leading-tight focus:outline-none focus:shadow-outline focus:border-blue-500"
+                        placeholder="rust, web"
+                    />
+                </div>
+                <div class="mb-6">
+                    <label for="project-publish-at" class="block text-navy-blue-custom-700 text-sm font-bold mb-2">"Publish At (UTC, optional):"</label>
+                    <input
+                        id="project-publish-at"
+                        type="datetime-local"
+                        prop:value=project_publish_at
+                        on:input=move |ev| set_project_publish_at.set(event_target_value(&ev))
+                        class="shadow appearance-none border rounded py-2 px-3 text-navy-blue-custom-700 leading-tight focus:outline-none focus:shadow-outline focus:border-blue-500"
+                    />
+                </div>
+                <Show
+                    when=move || editing_project_id.get().is_some()
+                    fallback=move || view! {
+                        <div class="flex gap-2">
+                            <button
+                                on:click=move |_| publish_project(false)
+                                class="bg-navy-blue-custom text-sandy-beige font-bold py-2 px-4 rounded hover:bg-navy-blue-custom-dark focus:outline-none focus:shadow-outline"
+                            >
+                                "Publish Project"
+                            </button>
+                            <button
+                                on:click=move |_| publish_project(true)
+                                class="border rounded font-bold py-2 px-4"
+                            >
+                                "Save as Draft"
+                            </button>
+                        </div>
+                    }
                 >
-                    "Publish Project"
-                </button>
+                    <div class="flex gap-2">
+                        <button
+                            on:click=move |_| save_project(false)
+                            class="bg-navy-blue-custom text-sandy-beige font-bold py-2 px-4 rounded hover:bg-navy-blue-custom-dark focus:outline-none focus:shadow-outline"
+                        >
+                            "Save and Publish"
+                        </button>
+                        <button
+                            on:click=move |_| save_project(true)
+                            class="border rounded font-bold py-2 px-4"
+                        >
+                            "Save as Draft"
+                        </button>
+                        <button
+                            on:click=on_cancel_project_edit
+                            class="border rounded font-bold py-2 px-4"
+                        >
+                            "Cancel"
+                        </button>
+                    </div>
+                </Show>
             </section>
 
             <p class="mt-4 text-sm text-navy-blue-custom-600 text-center">{publish_status}</p>
+
+            <section class="p-6 rounded-lg shadow-lg mb-8">
+                <h2 class="text-2xl font-semibold mb-4 text-navy-blue-custom-600">"Media Library"</h2>
+                <div class="flex gap-2 items-center mb-2">
+                    <input
+                        type="file"
+                        accept="image/png,image/jpeg,image/gif,image/webp"
+                        node_ref=file_input
+                        class="py-2"
+                    />
+                    <button
+                        on:click=on_upload
+                        class="bg-navy-blue-custom text-sandy-beige font-bold py-2 px-4 rounded hover:bg-navy-blue-custom-dark focus:outline-none focus:shadow-outline"
+                    >
+                        "Upload"
+                    </button>
+                </div>
+                <p class="text-sm mb-4">{upload_status}</p>
+                <Suspense fallback=|| view! { <p>"Loading media..."</p> }>
+                    {move || {
+                        media.read().clone().map(|res: Result<Vec<MediaRecord>, ServerFnError>| match res {
+                            Ok(items) if !items.is_empty() => view! {
+                                <div class="grid grid-cols-2 md:grid-cols-4 lg:grid-cols-6 gap-4">
+                                    {items
+                                        .into_iter()
+                                        .map(|item| {
+                                            let markdown = image_markdown(&item);
+                                            let markdown_for_project = markdown.clone();
+                                            let url = item.url.clone();
+                                            view! {
+                                                <div class="bg-charcoal-custom rounded-lg shadow-lg p-2 text-sm">
+                                                    <img src=item.thumbnail_url.clone() alt=item.original_name.clone() loading="lazy" class="w-full h-24 object-cover rounded mb-2"/>
+                                                    <p class="truncate" title=item.original_name.clone()>{item.original_name.clone()}</p>
+                                                    <p class="opacity-75 mb-2">{format_size(item.size)}</p>
+                                                    <div class="flex flex-col gap-1">
+                                                        <button
+                                                            on:click=move |_| set_blog_content.update(|content| append_block(content, &markdown))
+                                                            class="border rounded px-2"
+                                                        >"Insert into Post"</button>
+                                                        <button
+                                                            on:click=move |_| set_project_description.update(|description| append_block(description, &markdown_for_project))
+                                                            class="border rounded px-2"
+                                                        >"Insert into Case Study"</button>
+                                                        <button
+                                                            on:click=move |_| set_project_cover_image.set(url.clone())
+                                                            class="border rounded px-2"
+                                                        >"Use as Project Cover"</button>
+                                                    </div>
+                                                </div>
+                                            }
+                                        })
+                                        .collect_view()}
+                                </div>
+                            }.into_any(),
+
+                            Ok(_) => view! { <p>"Nothing uploaded yet"</p> }.into_any(),
+
+                            Err(e) => view! {
+                                <p>{format!("Error loading media: {}", e)}</p>
+                            }.into_any(),
+                        })
+                        .unwrap_or_else(|| view! { <p>"Loading..."</p> }.into_any())
+                    }}
+                </Suspense>
+            </section>
+
+            <ContactInbox/>
+            <AccountEmailStatus/>
+            <SiteSettingsEditor/>
+            <HomeSectionsEditor/>
             // deletion code
             <div class="mb-6">
                     <h3 class="text-xl font-semibold mb-2">"Delete a Project"</h3>
@@ -830,11 +3323,27 @@ fn AdminPanel() -> impl IntoView {
                                 each=move || vec.clone()
                                 key=|project| project.id.clone()
                                 children=move |project: ProjectRecord| {
+                                    let project_for_edit = project.clone();
                                     view! {
                                         <div class="bg-charcoal-custom rounded-lg shadow-lg p-6">
                                             <h1 class="text-xl font-bold mb-2">{project.id}</h1>
                                             <h2 class="text-xl font-bold mb-2">{project.title}</h2>
+                                            <StatusBadge status=project.status publish_at=project.publish_at/>
                                             <p>{project.content}</p>
+                                            <button
+                                                on:click=move |_| {
+                                                    // Load the record into the form above
+                                                    set_project_title.set(project_for_edit.title.clone());
+                                                    set_project_content.set(project_for_edit.content.clone());
+                                                    set_project_link.set(project_for_edit.link.clone());
+                                                    set_project_description.set(project_for_edit.description.clone());
+                                                    set_project_publish_at.set(project_for_edit.publish_at.map(format_datetime_local).unwrap_or_default());
+                                                    set_project_tags.set(join_tags(&project_for_edit.tags));
+                                                    set_project_cover_image.set(project_for_edit.cover_image.clone().unwrap_or_default());
+                                                    set_editing_project_id.set(Some(project_for_edit.id.clone()));
+                                                }
+                                                class="mt-4 bg-navy-blue-custom text-sandy-beige font-bold py-2 px-4 rounded"
+                                            >"Edit"</button>
                                         </div>
                                     }
                                 }
@@ -860,11 +3369,24 @@ fn AdminPanel() -> impl IntoView {
                                 each=move || vec.clone()
                                 key=|blog| blog.id.clone()
                                 children=move |blog: BlogRecord| {
+                                    let blog_for_edit = blog.clone();
                                     view! {
                                         <div class="bg-charcoal-custom rounded-lg shadow-lg p-6">
                                             <h1 class="text-xl font-bold mb-2">{blog.id}</h1>
                                             <h2 class="text-xl font-bold mb-2">{blog.title}</h2>
+                                            <StatusBadge status=blog.status publish_at=blog.publish_at/>
                                             <p>{blog.content}</p>
+                                            <button
+                                                on:click=move |_| {
+                                                    // Load the record into the form above
+                                                    set_blog_title.set(blog_for_edit.title.clone());
+                                                    set_blog_content.set(blog_for_edit.content.clone());
+                                                    set_blog_publish_at.set(blog_for_edit.publish_at.map(format_datetime_local).unwrap_or_default());
+                                                    set_blog_tags.set(join_tags(&blog_for_edit.tags));
+                                                    set_editing_blog_id.set(Some(blog_for_edit.id.clone()));
+                                                }
+                                                class="mt-4 bg-navy-blue-custom text-sandy-beige font-bold py-2 px-4 rounded"
+                                            >"Edit"</button>
                                         </div>
                                     }
                                 }
@@ -885,3 +3407,410 @@ fn AdminPanel() -> impl IntoView {
         </div>
     }
 }
+
+// The admin panel's view of messages from the contact form. Opening a message marks it read.
+#[component]
+fn AccountEmailStatus() -> impl IntoView {
+    let send_action = ServerAction::<SendVerificationEmail>::new();
+    let account = Resource::new(move || send_action.version().get(), |_| async move { get_account_email().await });
+    let (status, set_status) = signal("".to_string());
+
+    Effect::new(move |_| match send_action.value().get() {
+        Some(Ok(())) => set_status.set(format!(
+            "A confirmation link is on its way. It works for {} hours.",
+            VERIFY_LINK_HOURS
+        )),
+        Some(Err(e)) => set_status.set(format!("Failed to send the link: {}", e)),
+        None => {}
+    });
+
+    view! {
+        <section class="p-6 rounded-lg shadow-lg mb-8">
+            <h2 class="text-2xl font-semibold mb-4 text-navy-blue-custom-600">"Account Email"</h2>
+            <Suspense fallback=|| view! { <p>"Loading..."</p> }>
+                {move || {
+                    account.read().clone().map(|res: Result<AccountEmail, ServerFnError>| match res {
+                        Ok(account) if account.verified => view! {
+                            <p>{format!("{} is confirmed.", account.email)}</p>
+                        }.into_any(),
+                        Ok(account) => view! {
+                            <p class="mb-4">
+                                {format!(
+                                    "{} hasn't been confirmed yet. Password reset links are sent there, so make sure it's right.",
+                                    account.email
+                                )}
+                            </p>
+                            <button
+                                on:click=move |_| { send_action.dispatch(SendVerificationEmail {}); }
+                                disabled=move || send_action.pending().get()
+                                class="border rounded font-bold py-2 px-4 disabled:opacity-50"
+                            >
+                                "Send Confirmation Link"
+                            </button>
+                        }.into_any(),
+                        Err(e) => view! { <p>{format!("Error loading account: {}", e)}</p> }.into_any(),
+                    })
+                }}
+            </Suspense>
+            <p>{status}</p>
+        </section>
+    }
+}
+
+#[component]
+fn ContactInbox() -> impl IntoView {
+    let (show_archived, set_show_archived) = signal(false);
+    let update_action = ServerAction::<UpdateContactMessage>::new();
+    let delete_action = ServerAction::<DeleteContactMessage>::new();
+    let messages = Resource::new(
+        move || (show_archived.get(), update_action.version().get(), delete_action.version().get()),
+        |(archived, _, _)| async move { get_contact_messages(archived).await },
+    );
+    let (open_message, set_open_message) = signal(None::<String>);
+    let (status, set_status) = signal("".to_string());
+
+    Effect::new(move |_| {
+        if let Some(Err(e)) = update_action.value().get() {
+            set_status.set(format!("Failed to update message: {}", e));
+        }
+    });
+    Effect::new(move |_| match delete_action.value().get() {
+        Some(Ok(())) => set_status.set("Message deleted.".to_string()),
+        Some(Err(e)) => set_status.set(format!("Failed to delete message: {}", e)),
+        None => {}
+    });
+
+    view! {
+        <section class="p-6 rounded-lg shadow-lg mb-8">
+            <h2 class="text-2xl font-semibold mb-4 text-navy-blue-custom-600">
+                {move || if show_archived.get() { "Archived Messages" } else { "Inbox" }}
+            </h2>
+            <button
+                on:click=move |_| set_show_archived.update(|archived| *archived = !*archived)
+                class="border rounded font-bold py-2 px-4 mb-4"
+            >
+                {move || if show_archived.get() { "Back to Inbox" } else { "Show Archived" }}
+            </button>
+            <Suspense fallback=|| view! { <p>"Loading messages..."</p> }>
+                {move || {
+                    messages.read().clone().map(|res: Result<Vec<ContactMessage>, ServerFnError>| match res {
+                        Ok(items) if !items.is_empty() => view! {
+                            <div class="flex flex-col gap-2">
+                                {items
+                                    .into_iter()
+                                    .map(|item| {
+                                        let id = item.id.clone();
+                                        let is_open = move || open_message.get().as_deref() == Some(id.as_str());
+                                        let (id_for_open, id_for_read, id_for_archive, id_for_delete) =
+                                            (item.id.clone(), item.id.clone(), item.id.clone(), item.id.clone());
+                                        let (read, archived) = (item.read, item.archived);
+                                        let mailto = format!("mailto:{}", item.email);
+                                        view! {
+                                            <div class="bg-charcoal-custom rounded-lg shadow-lg p-4">
+                                                <button
+                                                    on:click=move |_| {
+                                                        set_open_message.set(Some(id_for_open.clone()));
+                                                        if !read {
+                                                            update_action.dispatch(UpdateContactMessage { id: id_for_open.clone(), read: true, archived });
+                                                        }
+                                                    }
+                                                    class=if read { "w-full text-left" } else { "w-full text-left font-bold" }
+                                                >
+                                                    {item.name.clone()}" <"{item.email.clone()}"> · "{format_timestamp(item.created_at)}
+                                                </button>
+                                                <Show when=is_open.clone()>
+                                                    <p class="whitespace-pre-wrap my-2">{item.message.clone()}</p>
+                                                    <a href=mailto.clone() class="underline">"Reply"</a>
+                                                </Show>
+                                                <div class="flex gap-2 mt-2 text-sm">
+                                                    <button
+                                                        on:click=move |_| {
+                                                            update_action.dispatch(UpdateContactMessage { id: id_for_read.clone(), read: !read, archived });
+                                                        }
+                                                        class="border rounded px-2"
+                                                    >{if read { "Mark Unread" } else { "Mark Read" }}</button>
+                                                    <button
+                                                        on:click=move |_| {
+                                                            update_action.dispatch(UpdateContactMessage { id: id_for_archive.clone(), read, archived: !archived });
+                                                        }
+                                                        class="border rounded px-2"
+                                                    >{if archived { "Unarchive" } else { "Archive" }}</button>
+                                                    <button
+                                                        on:click=move |_| {
+                                                            delete_action.dispatch(DeleteContactMessage { id: id_for_delete.clone() });
+                                                        }
+                                                        class="bg-red-600 text-white rounded px-2 hover:bg-red-700"
+                                                    >"Delete"</button>
+                                                </div>
+                                            </div>
+                                        }
+                                    })
+                                    .collect_view()}
+                            </div>
+                        }.into_any(),
+
+                        Ok(_) => view! { <p>"No messages"</p> }.into_any(),
+
+                        Err(e) => view! {
+                            <p>{format!("Error loading messages: {}", e)}</p>
+                        }.into_any(),
+                    })
+                    .unwrap_or_else(|| view! { <p>"Loading..."</p> }.into_any())
+                }}
+            </Suspense>
+            <p class="mt-4 text-sm text-navy-blue-custom-600">{status}</p>
+        </section>
+    }
+}
+
+// Profile links are edited one per line, as the label and then the address.
+fn format_profile_links(links: &[ProfileLink]) -> String {
+    links.iter().map(|link| format!("{} {}", link.label, link.url)).collect::<Vec<_>>().join("\n")
+}
+
+fn parse_profile_links(text: &str) -> Vec<ProfileLink> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| match line.trim().rsplit_once(char::is_whitespace) {
+            Some((label, url)) => ProfileLink { label: label.trim().to_string(), url: url.to_string() },
+            None => ProfileLink { label: String::new(), url: line.trim().to_string() },
+        })
+        .collect()
+}
+
+// The admin panel's editor for the site's title, tagline, owner and contact details.
+#[component]
+fn SiteSettingsEditor() -> impl IntoView {
+    let settings = use_site_settings();
+    let current = settings.get_untracked();
+    let (site_title, set_site_title) = signal(current.site_title);
+    let (tagline, set_tagline) = signal(current.tagline);
+    let (owner_name, set_owner_name) = signal(current.owner_name);
+    let (email, set_email) = signal(current.email);
+    let (profile_links, set_profile_links) = signal(format_profile_links(&current.profile_links));
+    let (status, set_status) = signal("".to_string());
+    let update_action = ServerAction::<UpdateSiteSettings>::new();
+
+    Effect::new(move |_| match update_action.value().get() {
+        Some(Ok(saved)) => {
+            set_status.set("Settings saved.".to_string());
+            set_profile_links.set(format_profile_links(&saved.profile_links));
+            // The header and page titles follow straight away
+            settings.set(saved);
+        }
+        Some(Err(e)) => set_status.set(format!("Failed to save settings: {}", e)),
+        None => {}
+    });
+
+    let on_save = move |_| {
+        update_action.dispatch(UpdateSiteSettings {
+            settings: SiteSettings {
+                site_title: site_title.get_untracked(),
+                tagline: tagline.get_untracked(),
+                owner_name: owner_name.get_untracked(),
+                email: email.get_untracked(),
+                profile_links: parse_profile_links(&profile_links.get_untracked()),
+            },
+        });
+    };
+
+    let input_class = "shadow appearance-none border rounded w-full py-2 px-3 text-navy-blue-custom-700 leading-tight focus:outline-none focus:shadow-outline focus:border-blue-500";
+    view! {
+        <section class="p-6 rounded-lg shadow-lg mb-8">
+            <h2 class="text-2xl font-semibold mb-4 text-navy-blue-custom-600">"Site Settings"</h2>
+            <div class="mb-4">
+                <label for="site-title" class="block text-navy-blue-custom-700 text-sm font-bold mb-2">"Site Title:"</label>
+                <input id="site-title" type="text" prop:value=site_title on:input=move |ev| set_site_title.set(event_target_value(&ev)) class=input_class/>
+            </div>
+            <div class="mb-4">
+                <label for="site-tagline" class="block text-navy-blue-custom-700 text-sm font-bold mb-2">"Tagline:"</label>
+                <input id="site-tagline" type="text" prop:value=tagline on:input=move |ev| set_tagline.set(event_target_value(&ev)) class=input_class/>
+            </div>
+            <div class="mb-4">
+                <label for="site-owner" class="block text-navy-blue-custom-700 text-sm font-bold mb-2">"Owner Name:"</label>
+                <input id="site-owner" type="text" prop:value=owner_name on:input=move |ev| set_owner_name.set(event_target_value(&ev)) class=input_class/>
+            </div>
+            <div class="mb-4">
+                <label for="site-email" class="block text-navy-blue-custom-700 text-sm font-bold mb-2">"Contact Email:"</label>
+                <input id="site-email" type="email" prop:value=email on:input=move |ev| set_email.set(event_target_value(&ev)) class=input_class/>
+            </div>
+            <div class="mb-4">
+                <label for="site-profile-links" class="block text-navy-blue-custom-700 text-sm font-bold mb-2">"Profile Links:"</label>
+                <textarea
+                    id="site-profile-links"
+                    prop:value=profile_links
+                    on:input=move |ev| set_profile_links.set(event_target_value(&ev))
+                    class=format!("{} h-24 resize-y", input_class)
+                    placeholder="One per line, e.g. GitHub https://github.com/you"
+                ></textarea>
+            </div>
+            <button
+                on:click=on_save
+                class="bg-navy-blue-custom text-sandy-beige font-bold py-2 px-4 rounded hover:bg-navy-blue-custom-dark focus:outline-none focus:shadow-outline"
+            >
+                "Save Settings"
+            </button>
+            <p class="mt-4 text-sm text-navy-blue-custom-600">{status}</p>
+        </section>
+    }
+}
+
+// The admin panel's editor for the sections on the home page's wheel. A section's paragraphs
+// are edited in one box, separated by blank lines.
+#[component]
+fn HomeSectionsEditor() -> impl IntoView {
+    let create_action = ServerAction::<CreateHomeSection>::new();
+    let update_action = ServerAction::<UpdateHomeSection>::new();
+    let delete_action = ServerAction::<DeleteHomeSection>::new();
+    let reorder_action = ServerAction::<ReorderHomeSections>::new();
+    let sections = Resource::new(
+        move || (
+            create_action.version().get(),
+            update_action.version().get(),
+            delete_action.version().get(),
+            reorder_action.version().get(),
+        ),
+        |_| async move { get_home_sections().await },
+    );
+    let (new_section, set_new_section) = signal("".to_string());
+    let (status, set_status) = signal("".to_string());
+
+    Effect::new(move |_| match create_action.value().get() {
+        Some(Ok(())) => {
+            set_status.set("Section added.".to_string());
+            set_new_section.set("".to_string());
+        }
+        Some(Err(e)) => set_status.set(format!("Failed to add section: {}", e)),
+        None => {}
+    });
+    Effect::new(move |_| match update_action.value().get() {
+        Some(Ok(())) => set_status.set("Section saved.".to_string()),
+        Some(Err(e)) => set_status.set(format!("Failed to save section: {}", e)),
+        None => {}
+    });
+    Effect::new(move |_| match delete_action.value().get() {
+        Some(Ok(())) => set_status.set("Section deleted.".to_string()),
+        Some(Err(e)) => set_status.set(format!("Failed to delete section: {}", e)),
+        None => {}
+    });
+    Effect::new(move |_| {
+        if let Some(Err(e)) = reorder_action.value().get() {
+            set_status.set(format!("Failed to move section: {}", e));
+        }
+    });
+
+    // Swaps the sections at `index` and `index + 1`
+    let swap = move |mut ids: Vec<String>, index: usize| {
+        ids.swap(index, index + 1);
+        reorder_action.dispatch(ReorderHomeSections { ids });
+    };
+
+    view! {
+        <section class="p-6 rounded-lg shadow-lg mb-8">
+            <h2 class="text-2xl font-semibold mb-4 text-navy-blue-custom-600">"Home Page Sections"</h2>
+            <p class="text-sm mb-4">"Sections go round the wheel in this order, starting at the top."</p>
+            <Suspense fallback=|| view! { <p>"Loading sections..."</p> }>
+                {move || {
+                    sections.read().clone().map(|res: Result<Vec<HomeSection>, ServerFnError>| match res {
+                        Ok(items) => {
+                            let ids: Vec<String> = items.iter().map(|section| section.id.clone()).collect();
+                            let count = items.len();
+                            view! {
+                                <div class="flex flex-col gap-4">
+                                    {items
+                                        .into_iter()
+                                        .enumerate()
+                                        .map(|(index, section)| {
+                                            let (text, set_text) = signal(section.paragraphs.join("\n\n"));
+                                            let id_for_save = section.id.clone();
+                                            let id_for_delete = section.id;
+                                            let ids_for_up = ids.clone();
+                                            let ids_for_down = ids.clone();
+                                            view! {
+                                                <div class="bg-charcoal-custom rounded-lg shadow-lg p-4">
+                                                    <p class="text-sm opacity-75 mb-2">{format!("Section {} of {}", index + 1, count)}</p>
+                                                    <textarea
+                                                        prop:value=text
+                                                        on:input=move |ev| set_text.set(event_target_value(&ev))
+                                                        class="shadow appearance-none border rounded w-full py-2 px-3 text-navy-blue-custom-700 leading-tight focus:outline-none focus:shadow-outline focus:border-blue-500 h-24 resize-y"
+                                                    ></textarea>
+                                                    <div class="flex gap-2 mt-2">
+                                                        <button
+                                                            on:click=move |_| {
+                                                                update_action.dispatch(UpdateHomeSection {
+                                                                    id: id_for_save.clone(),
+                                                                    paragraphs: vec![text.get_untracked()],
+                                                                });
+                                                            }
+                                                            class="bg-navy-blue-custom text-sandy-beige font-bold py-2 px-4 rounded"
+                                                        >"Save"</button>
+                                                        <button
+                                                            on:click=move |_| swap(ids_for_up.clone(), index - 1)
+                                                            disabled=index == 0
+                                                            class="border rounded font-bold py-2 px-4 disabled:opacity-50"
+                                                        >"Move Up"</button>
+                                                        <button
+                                                            on:click=move |_| swap(ids_for_down.clone(), index)
+                                                            disabled=index + 1 == count
+                                                            class="border rounded font-bold py-2 px-4 disabled:opacity-50"
+                                                        >"Move Down"</button>
+                                                        <button
+                                                            on:click=move |_| {
+                                                                delete_action.dispatch(DeleteHomeSection { id: id_for_delete.clone() });
+                                                            }
+                                                            class="bg-red-600 text-white font-bold py-2 px-4 rounded hover:bg-red-700"
+                                                        >"Delete"</button>
+                                                    </div>
+                                                </div>
+                                            }
+                                        })
+                                        .collect_view()}
+                                </div>
+                            }.into_any()
+                        }
+
+                        Err(e) => view! {
+                            <p>{format!("Error loading sections: {}", e)}</p>
+                        }.into_any(),
+                    })
+                    .unwrap_or_else(|| view! { <p>"Loading..."</p> }.into_any())
+                }}
+            </Suspense>
+            <div class="mt-4">
+                <label for="new-home-section" class="block text-navy-blue-custom-700 text-sm font-bold mb-2">"New Section:"</label>
+                <textarea
+                    id="new-home-section"
+                    prop:value=new_section
+                    on:input=move |ev| set_new_section.set(event_target_value(&ev))
+                    class="shadow appearance-none border rounded w-full py-2 px-3 text-navy-blue-custom-700 leading-tight focus:outline-none focus:shadow-outline focus:border-blue-500 h-24 resize-y"
+                    placeholder="Separate paragraphs with a blank line..."
+                ></textarea>
+                <button
+                    on:click=move |_| {
+                        create_action.dispatch(CreateHomeSection { paragraphs: vec![new_section.get_untracked()] });
+                    }
+                    class="mt-2 bg-navy-blue-custom text-sandy-beige font-bold py-2 px-4 rounded hover:bg-navy-blue-custom-dark focus:outline-none focus:shadow-outline"
+                >
+                    "Add Section"
+                </button>
+            </div>
+            <p class="mt-4 text-sm text-navy-blue-custom-600">{status}</p>
+        </section>
+    }
+}
+
+// Shows an admin whether a post or project is public yet.
+#[component]
+fn StatusBadge(status: PublishStatus, publish_at: Option<OffsetDateTime>) -> impl IntoView {
+    let class = match status {
+        PublishStatus::Draft => "inline-block mb-2 px-2 py-1 rounded text-sm font-bold bg-gray-500 text-white",
+        PublishStatus::Published => "inline-block mb-2 px-2 py-1 rounded text-sm font-bold bg-green-600 text-white",
+        PublishStatus::Scheduled => "inline-block mb-2 px-2 py-1 rounded text-sm font-bold bg-yellow-500 text-black",
+    };
+    let text = match (status, publish_at) {
+        (PublishStatus::Scheduled, Some(publish_at)) => format!("Scheduled for {}", format_timestamp(publish_at)),
+        _ => status.label().to_string(),
+    };
+
+    view! { <span class=class>{text}</span> }
+}
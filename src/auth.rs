@@ -0,0 +1,381 @@
+// Server-only account handling: password hashing, user lookups and sessions in SurrealDB.
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use leptos::logging::log;
+use rand::rngs::OsRng as TokenRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::{RecordId, Surreal};
+use tower_cookies::cookie::SameSite;
+use tower_cookies::Cookie;
+
+use crate::config::site_url;
+use crate::password_reset::send_verification_email;
+
+pub const SESSION_COOKIE: &str = "session_token";
+// Sessions expire after this long without a request; every authenticated request pushes it back.
+pub const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+// --- Data Structures for SurrealDB ---
+#[derive(Debug, Clone, Deserialize)]
+pub struct User {
+    pub id: Thing,
+    pub email: String,
+    pub password_hash: String,
+    // When the owner followed a link emailed to the address, see `crate::password_reset`
+    #[serde(default)]
+    pub email_verified_at: Option<surrealdb::sql::Datetime>,
+}
+
+#[derive(Debug, Serialize)]
+struct NewUser {
+    email: String,
+    password_hash: String,
+}
+
+// Emails are compared case-insensitively, so always store and look them up in one form.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+// --- Password Hashing ---
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string())
+}
+
+// The hash output is compared in constant time by `PasswordVerifier`.
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    match PasswordHash::new(password_hash) {
+        Ok(parsed) => Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+// Argon2 takes tens of milliseconds of CPU on purpose, so these run on the blocking pool
+// rather than holding up every other request on the async worker.
+pub async fn hash_password_blocking(password: &str) -> anyhow::Result<String> {
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || hash_password(&password))
+        .await?
+        .map_err(|e| anyhow::anyhow!(e.to_string()))
+}
+
+async fn verify_password_blocking(password: &str, password_hash: String) -> bool {
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || verify_password(&password, &password_hash))
+        .await
+        .unwrap_or(false)
+}
+
+// A hash that is verified against when the email is unknown, so a failed lookup
+// takes as long as a wrong password and doesn't reveal which accounts exist.
+static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+
+fn dummy_hash() -> String {
+    DUMMY_HASH
+        .get_or_init(|| hash_password("not-a-real-password").expect("Failed to hash dummy password"))
+        .clone()
+}
+
+// Makes the dummy hash on start-up, so the first login with an unknown email isn't slower
+// than the rest.
+pub async fn init_dummy_hash() -> anyhow::Result<()> {
+    let hash = hash_password_blocking("not-a-real-password").await?;
+    let _ = DUMMY_HASH.set(hash);
+    Ok(())
+}
+
+// --- User Lookups ---
+pub async fn find_user_by_email(db: &Surreal<Db>, email: &str) -> Result<Option<User>, surrealdb::Error> {
+    let mut response = db
+        .query("SELECT * FROM user WHERE email = $email LIMIT 1")
+        .bind(("email", normalize_email(email)))
+        .await?;
+    let user: Option<User> = response.take(0)?;
+    Ok(user)
+}
+
+pub async fn find_user(db: &Surreal<Db>, user: &Thing) -> Result<Option<User>, surrealdb::Error> {
+    let mut response = db
+        .query("SELECT * FROM ONLY $user")
+        .bind(("user", user.clone()))
+        .await?;
+    response.take(0)
+}
+
+// Returns the user only when the email exists and the password matches.
+pub async fn authenticate(
+    db: &Surreal<Db>,
+    email: &str,
+    password: &str,
+) -> Result<Option<User>, surrealdb::Error> {
+    match find_user_by_email(db, email).await? {
+        Some(user) => {
+            let verified = verify_password_blocking(password, user.password_hash.clone()).await;
+            Ok(verified.then_some(user))
+        }
+        None => {
+            verify_password_blocking(password, dummy_hash()).await;
+            Ok(None)
+        }
+    }
+}
+
+pub async fn create_user(db: &Surreal<Db>, email: &str, password: &str) -> anyhow::Result<User> {
+    let password_hash = hash_password_blocking(password).await?;
+    let created: Option<User> = db
+        .create("user")
+        .content(NewUser {
+            email: normalize_email(email),
+            password_hash,
+        })
+        .await?;
+    created.ok_or_else(|| anyhow::anyhow!("the user wasn't created"))
+}
+
+// Creates the first admin account from `ADMIN_EMAIL` / `ADMIN_PASSWORD` if it doesn't exist yet.
+pub async fn bootstrap_admin(db: &Surreal<Db>) -> anyhow::Result<()> {
+    let (Ok(email), Ok(password)) = (std::env::var("ADMIN_EMAIL"), std::env::var("ADMIN_PASSWORD")) else {
+        return Ok(());
+    };
+    if find_user_by_email(db, &email).await?.is_none() {
+        let user = create_user(db, &email, &password).await?;
+        log!("Created admin account for {}", user.email);
+        // Nobody has shown the address is theirs yet
+        send_verification_email(db, &user.id, &user.email).await?;
+    }
+    Ok(())
+}
+
+// --- Sessions ---
+// Only the SHA-256 of a token is stored, so a copy of the database can't be used to log in.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Session {
+    pub id: Thing,
+    pub user: Thing,
+    // Sent back by the admin panel on every state-changing request, see `crate::csrf`
+    #[serde(default)]
+    pub csrf_token: String,
+}
+
+// Generates a 256-bit random token, encoded as hex for use in a cookie.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    TokenRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+// Creates a session for the user and returns the raw token to hand to the browser.
+pub async fn create_session(db: &Surreal<Db>, user: &Thing) -> Result<String, surrealdb::Error> {
+    let token = generate_token();
+    db.query("DELETE session WHERE expires_at < time::now()")
+        .query(
+            "CREATE session SET token_hash = $token_hash, csrf_token = $csrf_token, user = $user, \
+             created_at = time::now(), last_seen_at = time::now(), expires_at = time::now() + $ttl",
+        )
+        .bind(("token_hash", hash_token(&token)))
+        .bind(("csrf_token", generate_token()))
+        .bind(("user", user.clone()))
+        .bind(("ttl", surrealdb::sql::Duration::from(SESSION_IDLE_TIMEOUT)))
+        .await?
+        .check()?;
+    Ok(token)
+}
+
+// Looks up an unexpired session and slides its expiry forward.
+pub async fn validate_session(db: &Surreal<Db>, token: &str) -> Result<Option<Session>, surrealdb::Error> {
+    let mut response = db
+        .query(
+            "UPDATE session SET last_seen_at = time::now(), expires_at = time::now() + $ttl \
+             WHERE token_hash = $token_hash AND expires_at > time::now()",
+        )
+        .bind(("token_hash", hash_token(token)))
+        .bind(("ttl", surrealdb::sql::Duration::from(SESSION_IDLE_TIMEOUT)))
+        .await?;
+    let session: Option<Session> = response.take(0)?;
+    Ok(session)
+}
+
+pub async fn delete_session(db: &Surreal<Db>, token: &str) -> Result<(), surrealdb::Error> {
+    db.query("DELETE session WHERE token_hash = $token_hash")
+        .bind(("token_hash", hash_token(token)))
+        .await?
+        .check()?;
+    Ok(())
+}
+
+// Revokes every session the user has, on every device.
+pub async fn delete_user_sessions(db: &Surreal<Db>, user: &Thing) -> Result<(), surrealdb::Error> {
+    db.query("DELETE session WHERE user = $user")
+        .bind(("user", user.clone()))
+        .await?
+        .check()?;
+    Ok(())
+}
+
+// --- Login Lockout ---
+// Failed logins are counted per account and per IP in the `login_attempt` table, so a
+// lockout survives a restart. Past the threshold every further failure doubles the lockout.
+pub const ACCOUNT_FAILURE_THRESHOLD: u32 = 5;
+pub const IP_FAILURE_THRESHOLD: u32 = 20;
+const LOCKOUT_BASE: Duration = Duration::from_secs(30);
+const LOCKOUT_MAX: Duration = Duration::from_secs(24 * 60 * 60);
+// Failures older than this are forgotten rather than added to.
+const FAILURE_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub fn account_throttle_key(email: &str) -> String {
+    format!("account:{}", normalize_email(email))
+}
+
+pub fn ip_throttle_key(ip: std::net::IpAddr) -> String {
+    format!("ip:{}", ip)
+}
+
+pub fn lockout_duration(failures: u32, threshold: u32) -> Duration {
+    if failures < threshold {
+        return Duration::ZERO;
+    }
+    let doublings = (failures - threshold).min(16);
+    (LOCKOUT_BASE * 2u32.pow(doublings)).min(LOCKOUT_MAX)
+}
+
+#[derive(Debug, Deserialize)]
+struct LoginAttempt {
+    failures: u32,
+}
+
+// True if any of the keys is currently locked out.
+pub async fn is_locked_out(db: &Surreal<Db>, keys: &[String]) -> Result<bool, surrealdb::Error> {
+    let records: Vec<RecordId> = keys
+        .iter()
+        .map(|key| RecordId::from(("login_attempt", key.as_str())))
+        .collect();
+    let mut response = db
+        .query("SELECT failures FROM login_attempt WHERE id IN $records AND locked_until > time::now()")
+        .bind(("records", records))
+        .await?;
+    let locked: Vec<LoginAttempt> = response.take(0)?;
+    Ok(!locked.is_empty())
+}
+
+pub async fn record_login_failure(
+    db: &Surreal<Db>,
+    key: &str,
+    threshold: u32,
+) -> Result<(), surrealdb::Error> {
+    let record = RecordId::from(("login_attempt", key));
+    // Counted in one statement, so failures at the same moment can't both read the old count
+    let mut response = db
+        .query(
+            "UPSERT $record SET \
+             failures = IF last_failure_at > time::now() - $window THEN failures + 1 ELSE 1 END, \
+             last_failure_at = time::now() RETURN failures",
+        )
+        .bind(("record", record.clone()))
+        .bind(("window", surrealdb::sql::Duration::from(FAILURE_WINDOW)))
+        .await?;
+    let attempt: Option<LoginAttempt> = response.take(0)?;
+    let failures = attempt.map_or(1, |attempt| attempt.failures);
+
+    // Only ever lengthened, in case a failure with a lower count finishes last
+    db.query(
+        "UPDATE $record SET locked_until = \
+         IF locked_until > time::now() + $lockout THEN locked_until ELSE time::now() + $lockout END",
+    )
+    .bind(("record", record))
+    .bind(("lockout", surrealdb::sql::Duration::from(lockout_duration(failures, threshold))))
+    .await?
+    .check()?;
+    Ok(())
+}
+
+pub async fn clear_login_failures(db: &Surreal<Db>, key: &str) -> Result<(), surrealdb::Error> {
+    let _deleted: Option<LoginAttempt> = db.delete(("login_attempt", key)).await?;
+    Ok(())
+}
+
+// --- Session Cookies ---
+// Sent over HTTPS only when the site is served over it. Browsers treat `localhost` as
+// secure, so local development over plain HTTP still works.
+fn secure_cookies() -> bool {
+    site_url().starts_with("https://")
+}
+
+pub fn session_cookie(token: String) -> Cookie<'static> {
+    let mut cookie = Cookie::new(SESSION_COOKIE, token);
+    cookie.set_path("/");
+    cookie.set_same_site(SameSite::Lax);
+    cookie.set_http_only(true);
+    cookie.set_secure(secure_cookies());
+    cookie.set_max_age(time::Duration::seconds(SESSION_IDLE_TIMEOUT.as_secs() as i64));
+    cookie
+}
+
+// A cookie that overwrites the session cookie so the browser drops it.
+pub fn removal_cookie() -> Cookie<'static> {
+    let mut cookie = Cookie::new(SESSION_COOKIE, "");
+    cookie.set_path("/");
+    cookie.set_same_site(SameSite::Lax);
+    cookie.set_http_only(true);
+    cookie.set_secure(secure_cookies());
+    cookie.make_removal();
+    cookie
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lockouts_start_at_the_threshold_and_double() {
+        assert_eq!(lockout_duration(0, 5), Duration::ZERO);
+        assert_eq!(lockout_duration(4, 5), Duration::ZERO);
+        assert_eq!(lockout_duration(5, 5), LOCKOUT_BASE);
+        assert_eq!(lockout_duration(6, 5), LOCKOUT_BASE * 2);
+        assert_eq!(lockout_duration(8, 5), LOCKOUT_BASE * 8);
+    }
+
+    #[test]
+    fn lockouts_are_capped() {
+        assert_eq!(lockout_duration(30, 5), LOCKOUT_MAX);
+        assert_eq!(lockout_duration(u32::MAX, 5), LOCKOUT_MAX);
+    }
+
+    #[test]
+    fn throttle_keys_ignore_email_case() {
+        assert_eq!(account_throttle_key(" Admin@Example.com "), account_throttle_key("admin@example.com"));
+    }
+
+    #[test]
+    fn passwords_verify_against_their_own_hash_only() {
+        let hash = hash_password("correct horse battery staple").unwrap();
+        assert!(verify_password("correct horse battery staple", &hash));
+        assert!(!verify_password("correct horse battery stapler", &hash));
+        assert!(!verify_password("correct horse battery staple", "not a hash"));
+    }
+
+    #[test]
+    fn tokens_are_random_and_stored_hashed() {
+        let token = generate_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, generate_token());
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_ne!(hash_token(&token), token);
+    }
+}
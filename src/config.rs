@@ -0,0 +1,150 @@
+// Settings read from the environment.
+use leptos::logging::log;
+
+use crate::rate_limit::IpNetwork;
+
+// Where the site is served from, for links that leave the site such as those in feeds.
+// Set `SITE_URL` when serving from anywhere but the production domain.
+pub fn site_url() -> String {
+    std::env::var("SITE_URL")
+        .ok()
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| "https://shilohnova.xyz".to_string())
+}
+
+// Paths crawlers are asked to stay out of. `ROBOTS_DISALLOW` adds to them, comma separated,
+// and `ROBOTS_DISALLOW_ALL=true` asks crawlers to keep away from the whole site, e.g. on staging.
+pub fn robots_disallow() -> Vec<String> {
+    if std::env::var("ROBOTS_DISALLOW_ALL").is_ok_and(|value| value.trim() == "true") {
+        return vec!["/".to_string()];
+    }
+    let mut paths: Vec<String> = ["/adminpanel", "/login", "/api"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    if let Ok(extra) = std::env::var("ROBOTS_DISALLOW") {
+        for path in extra.split(',').map(str::trim).filter(|path| !path.is_empty()) {
+            if !paths.iter().any(|known| known == path) {
+                paths.push(path.to_string());
+            }
+        }
+    }
+    paths
+}
+
+// Where generated preview images are kept between requests. `OG_CACHE_DIR` moves it; by
+// default it sits next to the database, so it survives container restarts with it.
+pub fn og_cache_dir() -> std::path::PathBuf {
+    std::env::var("OG_CACHE_DIR")
+        .ok()
+        .filter(|dir| !dir.trim().is_empty())
+        .unwrap_or_else(|| "./data/og".to_string())
+        .into()
+}
+
+// Where uploaded media is stored and served from at `/media`. `MEDIA_DIR` moves it.
+pub fn media_dir() -> std::path::PathBuf {
+    std::env::var("MEDIA_DIR")
+        .ok()
+        .filter(|dir| !dir.trim().is_empty())
+        .unwrap_or_else(|| "./data/media".to_string())
+        .into()
+}
+
+// The largest upload accepted, in bytes: `MEDIA_MAX_BYTES`, or 10 MiB.
+pub fn media_max_bytes() -> usize {
+    std::env::var("MEDIA_MAX_BYTES")
+        .ok()
+        .and_then(|bytes| bytes.trim().parse().ok())
+        .unwrap_or(10 * 1024 * 1024)
+}
+
+// How outgoing email is sent, see `crate::mail`.
+pub enum SmtpSecurity {
+    // Upgrade a plain connection with STARTTLS, usually on port 587
+    StartTls,
+    // TLS from the start, usually on port 465
+    Tls,
+    // No encryption at all, only for a relay on the same machine
+    None,
+}
+
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: Option<u16>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub security: SmtpSecurity,
+}
+
+fn non_empty_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// The SMTP server mail goes out through: `SMTP_HOST`, with `SMTP_PORT`, `SMTP_USERNAME`,
+// `SMTP_PASSWORD` and `SMTP_SECURITY` ("starttls", the default, "tls" or "none"). Without
+// `SMTP_HOST`, mail is written to `mail_dir` instead of being sent.
+pub fn smtp_config() -> Option<SmtpConfig> {
+    let host = non_empty_var("SMTP_HOST")?;
+    let security = match non_empty_var("SMTP_SECURITY").as_deref() {
+        Some("tls") => SmtpSecurity::Tls,
+        Some("none") => SmtpSecurity::None,
+        _ => SmtpSecurity::StartTls,
+    };
+    Some(SmtpConfig {
+        host,
+        port: non_empty_var("SMTP_PORT").and_then(|port| port.parse().ok()),
+        username: non_empty_var("SMTP_USERNAME"),
+        password: non_empty_var("SMTP_PASSWORD"),
+        security,
+    })
+}
+
+// Who mail from the site comes from: `MAIL_FROM`, e.g. "Shiloh <site@example.com>", or a
+// no-reply address at the site's domain.
+pub fn mail_from() -> String {
+    non_empty_var("MAIL_FROM").unwrap_or_else(|| {
+        let url = site_url();
+        let host = url.split("://").nth(1).unwrap_or(&url);
+        let host = host.split([':', '/']).next().unwrap_or_default();
+        format!("no-reply@{}", host)
+    })
+}
+
+// Where mail is written when there's no SMTP server. `MAIL_DIR` moves it.
+pub fn mail_dir() -> std::path::PathBuf {
+    non_empty_var("MAIL_DIR")
+        .unwrap_or_else(|| "./data/mail".to_string())
+        .into()
+}
+
+// Who is told about new contact messages: `CONTACT_NOTIFY_EMAIL`, or else the email address
+// in the site settings.
+pub fn contact_notify_email() -> Option<String> {
+    non_empty_var("CONTACT_NOTIFY_EMAIL")
+}
+
+// Reverse proxies whose `X-Forwarded-For` is believed, see `crate::rate_limit::client_ip`:
+// `TRUSTED_PROXIES`, comma separated addresses or CIDR ranges, or just loopback. In Docker
+// the proxy's connections come from the bridge network's gateway instead, so that needs
+// adding, e.g. "172.16.0.0/12".
+pub fn trusted_proxies() -> Vec<IpNetwork> {
+    let Some(value) = non_empty_var("TRUSTED_PROXIES") else {
+        return ["127.0.0.0/8", "::1"].into_iter().filter_map(IpNetwork::parse).collect();
+    };
+    value
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .filter_map(|entry| {
+            let network = IpNetwork::parse(entry);
+            if network.is_none() {
+                log!("Ignoring \"{}\" in TRUSTED_PROXIES; it isn't an address or CIDR range", entry.trim());
+            }
+            network
+        })
+        .collect()
+}
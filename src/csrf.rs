@@ -0,0 +1,72 @@
+// CSRF protection for the cookie-authenticated admin endpoints.
+//
+// Every session gets its own random token. `shell` embeds it in a
+// `<meta name="csrf-token">` tag for signed-in admins, and the browser echoes it
+// back in the `X-CSRF-Token` header on every state-changing request, which
+// `csrf_middleware` in main.rs checks against the session.
+use std::future::Future;
+
+use futures::{Sink, Stream};
+use leptos::server_fn::client::browser::BrowserClient;
+use leptos::server_fn::client::Client;
+use leptos::server_fn::error::FromServerFnError;
+use leptos::server_fn::request::browser::BrowserRequest;
+use leptos::server_fn::response::browser::BrowserResponse;
+use leptos::server_fn::Bytes;
+
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+// Reads the token embedded by `shell`; there is no page to read it from on the server.
+pub fn csrf_token() -> Option<String> {
+    #[cfg(feature = "hydrate")]
+    {
+        leptos::prelude::document()
+            .query_selector("meta[name=csrf-token]")
+            .ok()
+            .flatten()
+            .and_then(|meta| meta.get_attribute("content"))
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        None
+    }
+}
+
+// --- Server Function Client ---
+// The browser client, plus the CSRF header. Used by every server function under `/api/admin`.
+pub struct CsrfClient;
+
+impl<E, IS, OS> Client<E, IS, OS> for CsrfClient
+where
+    E: FromServerFnError,
+    IS: FromServerFnError,
+    OS: FromServerFnError,
+{
+    type Request = BrowserRequest;
+    type Response = BrowserResponse;
+
+    fn send(req: Self::Request) -> impl Future<Output = Result<Self::Response, E>> + Send {
+        if let Some(token) = csrf_token() {
+            req.headers().set(CSRF_HEADER, &token);
+        }
+        <BrowserClient as Client<E, IS, OS>>::send(req)
+    }
+
+    fn open_websocket(
+        path: &str,
+    ) -> impl Future<
+        Output = Result<
+            (
+                impl Stream<Item = Result<Bytes, Bytes>> + Send + 'static,
+                impl Sink<Bytes> + Send + 'static,
+            ),
+            E,
+        >,
+    > + Send {
+        <BrowserClient as Client<E, IS, OS>>::open_websocket(path)
+    }
+
+    fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+        <BrowserClient as Client<E, IS, OS>>::spawn(future)
+    }
+}
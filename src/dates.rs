@@ -0,0 +1,34 @@
+// Dates as the admin panel edits them and the pages show them. Everything is in UTC.
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{OffsetDateTime, PrimitiveDateTime};
+
+// The value of an `<input type="datetime-local">`, e.g. `2025-01-31T09:30`.
+pub fn format_datetime_local(dt: OffsetDateTime) -> String {
+    dt.format(format_description!("[year]-[month]-[day]T[hour]:[minute]"))
+        .unwrap_or_default()
+}
+
+// Browsers may add seconds to the value, which are dropped. Empty or invalid input is `None`.
+pub fn parse_datetime_local(value: &str) -> Option<OffsetDateTime> {
+    let value = value.trim().get(..16)?;
+    PrimitiveDateTime::parse(value, format_description!("[year]-[month]-[day]T[hour]:[minute]"))
+        .ok()
+        .map(PrimitiveDateTime::assume_utc)
+}
+
+// How times are sent to the server.
+pub fn format_rfc3339(dt: OffsetDateTime) -> String {
+    dt.format(&Rfc3339).unwrap_or_default()
+}
+
+pub fn format_timestamp(dt: OffsetDateTime) -> String {
+    dt.format(format_description!("[year]-[month]-[day] [hour]:[minute] UTC"))
+        .unwrap_or_default()
+}
+
+// As shown next to posts, e.g. `January 31, 2025`.
+pub fn format_date(dt: OffsetDateTime) -> String {
+    dt.format(format_description!("[month repr:long] [day padding:none], [year]"))
+        .unwrap_or_default()
+}
@@ -0,0 +1,392 @@
+// Feeds of the Views blog and of projects, as RSS 2.0, Atom and JSON Feed. Each is built
+// from the latest publicly visible records on every request, and sent with an ETag and
+// Last-Modified so feed readers polling for changes mostly get a 304 back.
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use leptos::logging::log;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use surrealdb::engine::local::Db;
+use surrealdb::Surreal;
+use time::format_description::well_known::{Rfc2822, Rfc3339};
+use time::macros::format_description;
+use time::{OffsetDateTime, PrimitiveDateTime};
+
+use crate::app::PUBLICLY_VISIBLE;
+use crate::config::site_url;
+use crate::markdown::{excerpt, render_markdown};
+use crate::settings::{site_settings, SiteSettings};
+
+const FEED_ITEMS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedKind {
+    Views,
+    Projects,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedKind {
+    fn table(self) -> &'static str {
+        match self {
+            FeedKind::Views => "blog_post",
+            FeedKind::Projects => "project",
+        }
+    }
+
+    fn title(self, site: &SiteSettings) -> String {
+        match self {
+            FeedKind::Views => format!("{} — Views", site.site_title),
+            FeedKind::Projects => format!("{} — Projects", site.site_title),
+        }
+    }
+
+    fn description(self, site: &SiteSettings) -> String {
+        match self {
+            FeedKind::Views => format!("Views by {}", site.owner_name),
+            FeedKind::Projects => format!("Projects by {}", site.owner_name),
+        }
+    }
+
+    // Where the records are listed on the site
+    fn page_path(self) -> &'static str {
+        match self {
+            FeedKind::Views => "/views",
+            FeedKind::Projects => "/projects",
+        }
+    }
+
+    // The routes main.rs serves each feed at
+    pub fn feed_path(self, format: FeedFormat) -> &'static str {
+        match (self, format) {
+            (FeedKind::Views, FeedFormat::Rss) => "/feed.xml",
+            (FeedKind::Views, FeedFormat::Atom) => "/atom.xml",
+            (FeedKind::Views, FeedFormat::Json) => "/feed.json",
+            (FeedKind::Projects, FeedFormat::Rss) => "/projects/feed.xml",
+            (FeedKind::Projects, FeedFormat::Atom) => "/projects/atom.xml",
+            (FeedKind::Projects, FeedFormat::Json) => "/projects/feed.json",
+        }
+    }
+}
+
+impl FeedFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+            FeedFormat::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+}
+
+// --- Loading ---
+#[derive(Debug, Deserialize)]
+struct FeedRecord {
+    title: String,
+    #[serde(default)]
+    slug: String,
+    content: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    publish_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    updated_at: OffsetDateTime,
+}
+
+struct FeedItem {
+    title: String,
+    url: String,
+    summary: String,
+    content_html: String,
+    published: OffsetDateTime,
+    updated: OffsetDateTime,
+}
+
+async fn load_items(db: &Surreal<Db>, kind: FeedKind) -> Result<Vec<FeedItem>, surrealdb::Error> {
+    let mut response = db
+        .query(format!(
+            "SELECT * FROM type::table($table) WHERE {PUBLICLY_VISIBLE} ORDER BY created_at DESC LIMIT $limit"
+        ))
+        .bind(("table", kind.table()))
+        .bind(("limit", FEED_ITEMS))
+        .await?;
+    let records: Vec<FeedRecord> = response.take(0)?;
+
+    let base = site_url();
+    Ok(records
+        .into_iter()
+        .map(|record| {
+            let mut content_html = render_markdown(&record.content);
+            if !record.description.is_empty() {
+                content_html.push_str(&render_markdown(&record.description));
+            }
+            // A scheduled record is published when its time comes, not when it was written
+            let published = record
+                .publish_at
+                .filter(|publish_at| *publish_at > record.created_at)
+                .unwrap_or(record.created_at);
+            FeedItem {
+                url: format!("{}{}/{}", base, kind.page_path(), record.slug),
+                summary: excerpt(&record.content, 200),
+                content_html,
+                title: record.title,
+                updated: record.updated_at.max(published),
+                published,
+            }
+        })
+        .collect())
+}
+
+// --- Rendering ---
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn rfc2822(dt: OffsetDateTime) -> String {
+    dt.format(&Rfc2822).unwrap_or_default()
+}
+
+fn rfc3339(dt: OffsetDateTime) -> String {
+    dt.format(&Rfc3339).unwrap_or_default()
+}
+
+fn render_rss(kind: FeedKind, site: &SiteSettings, items: &[FeedItem], updated: OffsetDateTime) -> String {
+    let base = site_url();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(
+        "<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" \
+         xmlns:content=\"http://purl.org/rss/1.0/modules/content/\">\n<channel>\n",
+    );
+    xml.push_str(&format!("<title>{}</title>\n", escape_xml(&kind.title(site))));
+    xml.push_str(&format!("<link>{}{}</link>\n", base, kind.page_path()));
+    xml.push_str(&format!("<description>{}</description>\n", escape_xml(&kind.description(site))));
+    xml.push_str("<language>en</language>\n");
+    xml.push_str(&format!("<lastBuildDate>{}</lastBuildDate>\n", rfc2822(updated)));
+    xml.push_str(&format!(
+        "<atom:link href=\"{}{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+        base,
+        kind.feed_path(FeedFormat::Rss)
+    ));
+    for item in items {
+        xml.push_str("<item>\n");
+        xml.push_str(&format!("<title>{}</title>\n", escape_xml(&item.title)));
+        xml.push_str(&format!("<link>{}</link>\n", escape_xml(&item.url)));
+        xml.push_str(&format!("<guid isPermaLink=\"true\">{}</guid>\n", escape_xml(&item.url)));
+        xml.push_str(&format!("<pubDate>{}</pubDate>\n", rfc2822(item.published)));
+        xml.push_str(&format!("<description>{}</description>\n", escape_xml(&item.summary)));
+        xml.push_str(&format!("<content:encoded>{}</content:encoded>\n", escape_xml(&item.content_html)));
+        xml.push_str("</item>\n");
+    }
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+fn render_atom(kind: FeedKind, site: &SiteSettings, items: &[FeedItem], updated: OffsetDateTime) -> String {
+    let base = site_url();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\" xml:lang=\"en\">\n");
+    xml.push_str(&format!("<title>{}</title>\n", escape_xml(&kind.title(site))));
+    xml.push_str(&format!("<subtitle>{}</subtitle>\n", escape_xml(&kind.description(site))));
+    xml.push_str(&format!("<id>{}{}</id>\n", base, kind.page_path()));
+    xml.push_str(&format!("<link href=\"{}{}\"/>\n", base, kind.page_path()));
+    xml.push_str(&format!(
+        "<link rel=\"self\" type=\"application/atom+xml\" href=\"{}{}\"/>\n",
+        base,
+        kind.feed_path(FeedFormat::Atom)
+    ));
+    xml.push_str(&format!("<updated>{}</updated>\n", rfc3339(updated)));
+    xml.push_str(&format!("<author><name>{}</name></author>\n", escape_xml(&site.owner_name)));
+    for item in items {
+        xml.push_str("<entry>\n");
+        xml.push_str(&format!("<title>{}</title>\n", escape_xml(&item.title)));
+        xml.push_str(&format!("<id>{}</id>\n", escape_xml(&item.url)));
+        xml.push_str(&format!("<link href=\"{}\"/>\n", escape_xml(&item.url)));
+        xml.push_str(&format!("<published>{}</published>\n", rfc3339(item.published)));
+        xml.push_str(&format!("<updated>{}</updated>\n", rfc3339(item.updated)));
+        xml.push_str(&format!("<summary>{}</summary>\n", escape_xml(&item.summary)));
+        xml.push_str(&format!("<content type=\"html\">{}</content>\n", escape_xml(&item.content_html)));
+        xml.push_str("</entry>\n");
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn render_json(kind: FeedKind, site: &SiteSettings, items: &[FeedItem]) -> String {
+    let base = site_url();
+    let items: Vec<serde_json::Value> = items
+        .iter()
+        .map(|item| {
+            serde_json::json!({
+                "id": item.url,
+                "url": item.url,
+                "title": item.title,
+                "summary": item.summary,
+                "content_html": item.content_html,
+                "date_published": rfc3339(item.published),
+                "date_modified": rfc3339(item.updated),
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": kind.title(site),
+        "description": kind.description(site),
+        "home_page_url": format!("{}{}", base, kind.page_path()),
+        "feed_url": format!("{}{}", base, kind.feed_path(FeedFormat::Json)),
+        "language": "en",
+        "authors": [{ "name": site.owner_name }],
+        "items": items,
+    })
+    .to_string()
+}
+
+// --- Conditional Responses ---
+fn http_date(dt: OffsetDateTime) -> String {
+    dt.to_offset(time::UtcOffset::UTC)
+        .format(format_description!(
+            "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+        ))
+        .unwrap_or_default()
+}
+
+fn parse_http_date(value: &str) -> Option<OffsetDateTime> {
+    PrimitiveDateTime::parse(
+        value,
+        format_description!(
+            "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+        ),
+    )
+    .ok()
+    .map(PrimitiveDateTime::assume_utc)
+}
+
+// Sends `body` with validators, or 304 Not Modified if the client's copy is still current.
+// `If-None-Match` wins over `If-Modified-Since` when a client sends both.
+pub fn cached_response(
+    request_headers: &HeaderMap,
+    content_type: &'static str,
+    body: String,
+    last_modified: OffsetDateTime,
+) -> Response {
+    let etag = format!("\"{}\"", &hex::encode(Sha256::digest(body.as_bytes()))[..32]);
+    let last_modified = last_modified.replace_nanosecond(0).unwrap_or(last_modified);
+
+    let not_modified = match request_headers.get(header::IF_NONE_MATCH) {
+        Some(value) => value
+            .to_str()
+            .map(|tags| tags.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"))
+            .unwrap_or(false),
+        None => request_headers
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_http_date)
+            .is_some_and(|since| last_modified <= since),
+    };
+
+    let mut response = if not_modified {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        ([(header::CONTENT_TYPE, content_type)], body).into_response()
+    };
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        headers.insert(header::ETAG, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&http_date(last_modified)) {
+        headers.insert(header::LAST_MODIFIED, value);
+    }
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("public, max-age=300"));
+    response
+}
+
+// Handles a request for one of the feeds.
+pub async fn serve_feed(db: &Surreal<Db>, headers: &HeaderMap, kind: FeedKind, format: FeedFormat) -> Response {
+    let items = match load_items(db, kind).await {
+        Ok(items) => items,
+        Err(e) => {
+            log!("Failed to read feed items from SurrealDB: {:?}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    // An empty feed never changes, so it is dated at the epoch
+    let updated = items
+        .iter()
+        .map(|item| item.updated)
+        .max()
+        .unwrap_or(OffsetDateTime::UNIX_EPOCH);
+
+    let site = site_settings();
+    let body = match format {
+        FeedFormat::Rss => render_rss(kind, &site, &items, updated),
+        FeedFormat::Atom => render_atom(kind, &site, &items, updated),
+        FeedFormat::Json => render_json(kind, &site, &items),
+    };
+    cached_response(headers, format.content_type(), body, updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modified() -> OffsetDateTime {
+        time::macros::datetime!(2026-01-01 12:00:00.5 UTC)
+    }
+
+    #[test]
+    fn xml_special_characters_are_escaped() {
+        assert_eq!(escape_xml(r#"<a href="x">Tom & Jerry's</a>"#), "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&apos;s&lt;/a&gt;");
+        assert_eq!(escape_xml("plain"), "plain");
+    }
+
+    #[test]
+    fn responses_carry_validators() {
+        let response = cached_response(&HeaderMap::new(), "application/json", "{}".to_string(), modified());
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().contains_key(header::ETAG));
+        assert_eq!(response.headers()[header::LAST_MODIFIED], "Thu, 01 Jan 2026 12:00:00 GMT");
+    }
+
+    #[test]
+    fn a_matching_etag_gets_not_modified() {
+        let first = cached_response(&HeaderMap::new(), "application/json", "{}".to_string(), modified());
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, first.headers()[header::ETAG].clone());
+        let again = cached_response(&headers, "application/json", "{}".to_string(), modified());
+        assert_eq!(again.status(), StatusCode::NOT_MODIFIED);
+
+        let changed = cached_response(&headers, "application/json", "[]".to_string(), modified());
+        assert_eq!(changed.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn if_modified_since_is_only_used_without_an_etag() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_MODIFIED_SINCE, HeaderValue::from_static("Thu, 01 Jan 2026 12:00:00 GMT"));
+        let response = cached_response(&headers, "application/json", "{}".to_string(), modified());
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"stale\""));
+        let response = cached_response(&headers, "application/json", "{}".to_string(), modified());
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,58 @@
+// Server-side syntax highlighting for fenced code blocks. Code is turned into spans
+// with `hl-` prefixed classes, coloured by the stylesheet from `theme_css`, so pages
+// need no JavaScript to show highlighted code.
+use std::sync::OnceLock;
+
+use leptos::logging::log;
+use syntect::highlighting::ThemeSet;
+use syntect::html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+// Any theme bundled with syntect can be picked with `HIGHLIGHT_THEME`.
+const DEFAULT_THEME: &str = "base16-ocean.dark";
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+// Highlights the code as `language`, which may be a name or file extension
+// such as `rust`, `rs`, `python` or `py`. Unknown languages are left plain.
+pub fn highlight_code(language: &str, code: &str) -> String {
+    let syntaxes = syntax_set();
+    let syntax = syntaxes
+        .find_syntax_by_token(language)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, syntaxes, CLASS_STYLE);
+    for line in LinesWithEndings::from(code) {
+        if let Err(e) = generator.parse_html_for_line_which_includes_newline(line) {
+            log!("Failed to highlight {} code: {}", language, e);
+            return format!("<pre class=\"hl-code\"><code>{}</code></pre>", escape_html(code));
+        }
+    }
+    format!("<pre class=\"hl-code\"><code>{}</code></pre>", generator.finalize())
+}
+
+// The stylesheet for the highlighted code, served at `/pkg/highlight.css`.
+pub fn theme_css() -> &'static str {
+    static CSS: OnceLock<String> = OnceLock::new();
+    CSS.get_or_init(|| {
+        let themes = ThemeSet::load_defaults();
+        let name = std::env::var("HIGHLIGHT_THEME").unwrap_or_else(|_| DEFAULT_THEME.to_string());
+        let theme = themes.themes.get(&name).unwrap_or_else(|| {
+            log!("Unknown HIGHLIGHT_THEME {}, using {}", name, DEFAULT_THEME);
+            &themes.themes[DEFAULT_THEME]
+        });
+        css_for_theme_with_class_style(theme, CLASS_STYLE).unwrap_or_else(|e| {
+            log!("Failed to generate the highlighting stylesheet: {}", e);
+            String::new()
+        })
+    })
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
@@ -0,0 +1,82 @@
+// The sections of text on the home page's wheel, kept in the `home_section` table and edited
+// from the admin panel. Each has its paragraphs and a `position`; where a section sits on the
+// wheel follows from its place in that order (see `wheel_placement` in `crate::app`).
+use serde::Serialize;
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Datetime;
+use surrealdb::{RecordId, Surreal};
+
+// What the home page said before its sections were editable
+const DEFAULT_SECTIONS: [&str; 4] = [
+    "Hi, I am Shiloh Antony John, a Stoic programmer who aspires to be an entrepreneur to do good to humanity. I am obsessed with gaining knowledge and solving problems with true innovation! Continue scrolling!!!",
+    "I operate at the digital frontier, where blockchain's revolutionary promise and emerging technologies like AI and smart contracts redefine human trust and commerce.",
+    "I leverage the unparalleled efficiency of Rust and the elegant simplicity of Python to engineer cutting-edge solutions that redefine what's possible in the tech world.",
+    "Linux is the bedrock of my development, fueling innovation and scaling my entrepreneurial ventures with its open-source power.",
+];
+
+#[derive(Serialize)]
+pub struct NewHomeSection {
+    pub paragraphs: Vec<String>,
+    pub position: u32,
+    pub created_at: Datetime,
+    pub updated_at: Datetime,
+}
+
+// Fills an empty `home_section` table with the default sections, so a new (or upgraded)
+// site doesn't start with a blank home page.
+pub async fn seed_home_sections(db: &Surreal<Db>) -> Result<(), surrealdb::Error> {
+    let mut response = db.query("SELECT VALUE id FROM home_section LIMIT 1").await?;
+    let existing: Vec<surrealdb::sql::Thing> = response.take(0)?;
+    if !existing.is_empty() {
+        return Ok(());
+    }
+
+    for (position, text) in DEFAULT_SECTIONS.into_iter().enumerate() {
+        let now = Datetime::default();
+        let _created: Option<serde::de::IgnoredAny> = db
+            .create("home_section")
+            .content(NewHomeSection {
+                paragraphs: vec![text.to_string()],
+                position: position as u32,
+                created_at: now.clone(),
+                updated_at: now,
+            })
+            .await?;
+    }
+    Ok(())
+}
+
+// Gives each section its place in `ids`, in one transaction so a failure part way doesn't
+// leave two sections in the same place. `ids` has to be every section exactly once; a list
+// from before a section was added or deleted is turned down rather than half applied.
+pub async fn reorder_sections(db: &Surreal<Db>, ids: &[RecordId]) -> Result<(), surrealdb::Error> {
+    let mut query = db
+        .query("BEGIN TRANSACTION")
+        .query(
+            "LET $current = (SELECT VALUE id FROM home_section); \
+             IF array::len(array::distinct($ids)) != array::len($ids) \
+                 OR array::len($ids) != array::len($current) \
+                 OR array::len(array::complement($current, $ids)) > 0 { \
+                 THROW 'The sections have changed since the page was loaded. Please reload it and try again.'; \
+             }",
+        )
+        .bind(("ids", ids.to_vec()));
+    for (position, id) in ids.iter().enumerate() {
+        query = query
+            .query(format!("UPDATE $id{position} SET position = $position{position}"))
+            .bind((format!("id{position}"), id.clone()))
+            .bind((format!("position{position}"), position as u32));
+    }
+    query.query("COMMIT TRANSACTION").await?.check()?;
+    Ok(())
+}
+
+// Splits the admin panel's text into paragraphs at blank lines, dropping empty ones.
+pub fn clean_paragraphs(paragraphs: Vec<String>) -> Vec<String> {
+    paragraphs
+        .iter()
+        .flat_map(|paragraph| paragraph.split("\n\n"))
+        .map(|paragraph| paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect()
+}
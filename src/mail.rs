@@ -0,0 +1,209 @@
+// Outgoing email. Handlers pass an `Email` to `queue_email`, which returns straight away; a
+// background task started by `start_mailer` hands it to the configured `Mailer`, so a slow or
+// unreachable mail server never holds up a request.
+//
+// With `SMTP_HOST` set mail goes out over SMTP (see `crate::config::smtp_config`). Without it
+// each email is written to `MAIL_DIR` as an .eml file and logged, for development and tests.
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use leptos::logging::log;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use time::OffsetDateTime;
+use tokio::sync::mpsc;
+
+use crate::config::{mail_dir, mail_from, smtp_config, SmtpConfig, SmtpSecurity};
+
+// How long to wait before each retry of an email the server didn't take
+const RETRY_DELAYS: [Duration; 2] = [Duration::from_secs(30), Duration::from_secs(5 * 60)];
+
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    pub reply_to: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum MailError {
+    // An address that can't be used; trying again won't help
+    Address(String),
+    Transport(String),
+}
+
+impl fmt::Display for MailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MailError::Address(reason) => write!(f, "invalid address: {}", reason),
+            MailError::Transport(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+pub trait Mailer: Send + Sync {
+    fn send<'a>(&'a self, email: &'a Email) -> BoxFuture<'a, Result<(), MailError>>;
+}
+
+fn mailbox(address: &str) -> Result<Mailbox, MailError> {
+    address
+        .parse()
+        .map_err(|e: lettre::address::AddressError| MailError::Address(format!("{} ({})", address, e)))
+}
+
+fn build_message(from: &Mailbox, email: &Email) -> Result<Message, MailError> {
+    let mut builder = Message::builder()
+        .from(from.clone())
+        .to(mailbox(&email.to)?)
+        .subject(email.subject.clone())
+        .header(ContentType::TEXT_PLAIN);
+    if let Some(reply_to) = &email.reply_to {
+        builder = builder.reply_to(mailbox(reply_to)?);
+    }
+    builder
+        .body(email.body.clone())
+        .map_err(|e| MailError::Address(e.to_string()))
+}
+
+// --- SMTP ---
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(config: SmtpConfig, from: &str) -> Result<SmtpMailer, MailError> {
+        let transport_error = |e: lettre::transport::smtp::Error| MailError::Transport(e.to_string());
+        let mut builder = match config.security {
+            SmtpSecurity::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host).map_err(transport_error)?
+            }
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host).map_err(transport_error)?,
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+        };
+        if let Some(port) = config.port {
+            builder = builder.port(port);
+        }
+        if let Some(username) = config.username {
+            builder = builder.credentials(Credentials::new(username, config.password.unwrap_or_default()));
+        }
+        Ok(SmtpMailer {
+            transport: builder.build(),
+            from: mailbox(from)?,
+        })
+    }
+}
+
+impl Mailer for SmtpMailer {
+    fn send<'a>(&'a self, email: &'a Email) -> BoxFuture<'a, Result<(), MailError>> {
+        Box::pin(async move {
+            let message = build_message(&self.from, email)?;
+            self.transport
+                .send(message)
+                .await
+                .map_err(|e| MailError::Transport(e.to_string()))?;
+            Ok(())
+        })
+    }
+}
+
+// --- Files ---
+pub struct FileMailer {
+    dir: PathBuf,
+    from: Mailbox,
+}
+
+impl FileMailer {
+    pub fn new(dir: PathBuf, from: &str) -> Result<FileMailer, MailError> {
+        Ok(FileMailer { dir, from: mailbox(from)? })
+    }
+}
+
+impl Mailer for FileMailer {
+    fn send<'a>(&'a self, email: &'a Email) -> BoxFuture<'a, Result<(), MailError>> {
+        Box::pin(async move {
+            let message = build_message(&self.from, email)?;
+            let now = OffsetDateTime::now_utc();
+            let path = self.dir.join(format!("{}-{:09}.eml", now.unix_timestamp(), now.nanosecond()));
+            let written = match tokio::fs::create_dir_all(&self.dir).await {
+                Ok(()) => tokio::fs::write(&path, message.formatted()).await,
+                Err(e) => Err(e),
+            };
+            written.map_err(|e| MailError::Transport(format!("couldn't write {}: {}", path.display(), e)))?;
+            log!("Mail to {} ({}) written to {}", email.to, email.subject, path.display());
+            Ok(())
+        })
+    }
+}
+
+// The mailer the environment asks for, see `crate::config::smtp_config`.
+pub fn mailer_from_env() -> Result<Box<dyn Mailer>, MailError> {
+    match smtp_config() {
+        Some(config) => Ok(Box::new(SmtpMailer::new(config, &mail_from())?)),
+        None => Ok(Box::new(FileMailer::new(mail_dir(), &mail_from())?)),
+    }
+}
+
+// --- Queue ---
+fn queue() -> &'static OnceLock<mpsc::UnboundedSender<Email>> {
+    static QUEUE: OnceLock<mpsc::UnboundedSender<Email>> = OnceLock::new();
+    &QUEUE
+}
+
+// Starts the task that sends queued mail. Called once on start-up, from inside the runtime.
+pub fn start_mailer(mailer: Box<dyn Mailer>) {
+    let mailer: Arc<dyn Mailer> = Arc::from(mailer);
+    let (sender, mut receiver) = mpsc::unbounded_channel::<Email>();
+    if queue().set(sender).is_err() {
+        log!("The mailer was already started");
+        return;
+    }
+    tokio::spawn(async move {
+        while let Some(email) = receiver.recv().await {
+            // Each on its own, so one waiting to be retried doesn't hold up the rest
+            let mailer = mailer.clone();
+            tokio::spawn(async move { deliver(mailer.as_ref(), &email).await });
+        }
+    });
+}
+
+async fn deliver(mailer: &dyn Mailer, email: &Email) {
+    let mut delays = RETRY_DELAYS.iter();
+    loop {
+        match mailer.send(email).await {
+            Ok(()) => return,
+            Err(MailError::Transport(reason)) => match delays.next() {
+                Some(delay) => {
+                    log!("Failed to send mail to {}, trying again in {:?}: {}", email.to, delay, reason);
+                    tokio::time::sleep(*delay).await;
+                }
+                None => {
+                    log!("Gave up sending mail to {} ({}): {}", email.to, email.subject, reason);
+                    return;
+                }
+            },
+            Err(e) => {
+                log!("Couldn't send mail to {} ({}): {}", email.to, email.subject, e);
+                return;
+            }
+        }
+    }
+}
+
+// Queues an email to be sent in the background. Never waits, and never fails: problems
+// sending it are logged.
+pub fn queue_email(email: Email) {
+    match queue().get() {
+        Some(sender) => {
+            if sender.send(email).is_err() {
+                log!("The mail queue has stopped; an email was dropped");
+            }
+        }
+        None => log!("No mailer has been started; mail to {} was dropped", email.to),
+    }
+}
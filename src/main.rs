@@ -2,8 +2,8 @@
 #[tokio::main]
 async fn main() {
     use axum::body::Body;
-    use axum::extract::{FromRef, Json, State};
-    use axum::http::{Request, StatusCode};
+    use axum::extract::{ConnectInfo, DefaultBodyLimit, FromRef, Json, Multipart, Path, State};
+    use axum::http::{header, HeaderMap, Request, StatusCode};
     use axum::middleware::{self, Next};
     use axum::response::{IntoResponse, Response};
     use axum::routing::{get, post};
@@ -11,14 +11,38 @@ async fn main() {
     use leptos::logging::log;
     use leptos::prelude::*;
     use leptos::context::provide_context;
-    use leptos_axum::{generate_route_list, render_app_to_stream_with_context};
+    use leptos_axum::{
+        generate_route_list, render_app_async_with_context, render_app_to_stream_with_context,
+    };
+    use leptos_router::SsrMode;
     use serde::{Deserialize, Serialize};
     use shilohnova::app::*;
+    use shilohnova::auth::{
+        account_throttle_key, authenticate, bootstrap_admin, clear_login_failures,
+        create_session, delete_session, init_dummy_hash, ip_throttle_key, is_locked_out,
+        record_login_failure, removal_cookie, session_cookie, validate_session, Session, ACCOUNT_FAILURE_THRESHOLD,
+        IP_FAILURE_THRESHOLD, SESSION_COOKIE,
+    };
+    use shilohnova::rate_limit::{client_ip, rate_limit_middleware, RateLimiter};
+    use shilohnova::slug::{backfill_slugs, unique_slug};
+    use shilohnova::tags::set_tags;
+    use std::net::SocketAddr;
+    use shilohnova::csrf::CSRF_HEADER;
+    use shilohnova::feed::{serve_feed, FeedFormat, FeedKind};
+    use shilohnova::config::{media_dir, media_max_bytes};
+    use shilohnova::highlight::theme_css;
+    use shilohnova::home::seed_home_sections;
+    use shilohnova::mail::{mailer_from_env, start_mailer};
+    use shilohnova::media::{load_media_index, store_upload, UploadError};
+    use shilohnova::og::serve_og_image;
+    use shilohnova::settings::load_site_settings;
+    use shilohnova::sitemap::{robots_txt, serve_sitemap};
+    use subtle::ConstantTimeEq;
     use surrealdb::engine::local::{Db, RocksDb};
-    use surrealdb::Surreal;
+    use surrealdb::sql::Datetime;
+    use surrealdb::{RecordId, Surreal};
     use tower::ServiceExt;
-    use tower_cookies::cookie::SameSite;
-    use tower_cookies::{Cookie, CookieManagerLayer, Cookies};
+    use tower_cookies::{CookieManagerLayer, Cookies};
     use tower_http::services::ServeDir;
     use leptos_axum::handle_server_fns_with_context;
     // --- Axum State Struct ---
@@ -40,8 +64,20 @@ async fn main() {
     pub struct BlogPost {
         pub title: String,
         pub content: String,
-        // The `created_at` field will be set by SurrealDB's `time::now()`
-        // so we don't need to pass it in from the client
+        // Generated from the title when the post is created, whatever the client sends
+        #[serde(default)]
+        pub slug: String,
+        #[serde(default)]
+        pub status: PublishStatus,
+        pub publish_at: Option<Datetime>,
+        // Stored as `tagged` edges by `set_tags` rather than on the record
+        #[serde(default, skip_serializing)]
+        pub tags: Vec<String>,
+        // Set by `publish_blog_post`, whatever the client sends
+        #[serde(default)]
+        pub created_at: Datetime,
+        #[serde(default)]
+        pub updated_at: Datetime,
     }
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,47 +85,130 @@ async fn main() {
         pub title: String,
         pub content: String,
         pub link: String,
-        // The `created_at` field will be set by SurrealDB's `time::now()`
+        // The long-form write-up shown on the project's own page
+        #[serde(default)]
+        pub description: String,
+        // Generated from the title when the project is created, whatever the client sends
+        #[serde(default)]
+        pub slug: String,
+        #[serde(default)]
+        pub status: PublishStatus,
+        pub publish_at: Option<Datetime>,
+        // Stored as `tagged` edges by `set_tags` rather than on the record
+        #[serde(default, skip_serializing)]
+        pub tags: Vec<String>,
+        #[serde(default)]
+        pub cover_image: Option<String>,
+        // Set by `publish_project`, whatever the client sends
+        #[serde(default)]
+        pub created_at: Datetime,
+        #[serde(default)]
+        pub updated_at: Datetime,
+    }
+
+    // Just the id of a record that was created
+    #[derive(Debug, Deserialize)]
+    struct CreatedRecord {
+        id: RecordId,
     }
 
     // This is the SurrealDB connection
     async fn db_connect() -> Result<Surreal<Db>, surrealdb::Error> {
         let db = Surreal::new::<RocksDb>("./data/shilohnova.db").await?;
         db.use_ns("site").use_db("main").await?;
+        // Tables, indexes and field defaults the handlers rely on
+        db.query(include_str!("schema.surql")).await?.check()?;
         Ok(db)
     }
 
     // --- Authentication Handler ---
     async fn login_handler(
-        State(_db): State<Surreal<Db>>,
+        State(db): State<Surreal<Db>>,
+        ConnectInfo(peer): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
         cookies: Cookies,
         Json(payload): Json<AuthPayload>,
     ) -> Result<Json<String>, StatusCode> {
         log!("Received login request for: {}", payload.email);
 
-        if payload.email == "test@example.com" && payload.password == "password123" {
-            let mut cookie = Cookie::new("session_token", "mock_session_token_123");
-            cookie.set_path("/");
-            cookie.set_same_site(SameSite::Lax);
-            cookie.set_http_only(true);
-            cookies.add(cookie);
+        let db_error = |e: surrealdb::Error| {
+            log!("Failed to access SurrealDB: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        let account_key = account_throttle_key(&payload.email);
+        let ip_key = ip_throttle_key(client_ip(&headers, peer));
+
+        // Locked out accounts and addresses don't get their password checked at all
+        if is_locked_out(&db, &[account_key.clone(), ip_key.clone()])
+            .await
+            .map_err(db_error)?
+        {
+            log!("Login attempt while locked out for: {}", payload.email);
+            return Err(StatusCode::TOO_MANY_REQUESTS);
+        }
+
+        let user = authenticate(&db, &payload.email, &payload.password)
+            .await
+            .map_err(db_error)?;
+
+        if let Some(user) = user {
+            clear_login_failures(&db, &account_key).await.map_err(db_error)?;
+            let token = create_session(&db, &user.id).await.map_err(db_error)?;
+            cookies.add(session_cookie(token));
             log!("User authenticated, cookie set.");
             Ok(Json("Login successful!".to_string()))
         } else {
             log!("Authentication failed for: {}", payload.email);
+            record_login_failure(&db, &account_key, ACCOUNT_FAILURE_THRESHOLD)
+                .await
+                .map_err(db_error)?;
+            record_login_failure(&db, &ip_key, IP_FAILURE_THRESHOLD)
+                .await
+                .map_err(db_error)?;
             Err(StatusCode::UNAUTHORIZED)
         }
     }
 
+    // --- Logout Handler ---
+    async fn logout_handler(
+        State(db): State<Surreal<Db>>,
+        cookies: Cookies,
+    ) -> Result<StatusCode, StatusCode> {
+        if let Some(token) = cookies.get(SESSION_COOKIE).map(|c| c.value().to_string()) {
+            delete_session(&db, &token).await.map_err(|e| {
+                log!("Failed to delete session from SurrealDB: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        }
+        cookies.add(removal_cookie());
+        Ok(StatusCode::NO_CONTENT)
+    }
+
     // --- Protected Routes Middleware ---
     async fn auth_middleware(
+        State(db): State<Surreal<Db>>,
         cookies: Cookies,
-        request: Request<Body>,
+        mut request: Request<Body>,
         next: Next,
     ) -> Result<Response, StatusCode> {
-        let session_token = cookies.get("session_token").map(|c| c.value().to_string());
-
-        if session_token == Some("mock_session_token_123".to_string()) {
+        let session_token = cookies.get(SESSION_COOKIE).map(|c| c.value().to_string());
+
+        let session = match session_token {
+            Some(token) => validate_session(&db, &token)
+                .await
+                .map_err(|e| {
+                    log!("Failed to read session from SurrealDB: {:?}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?
+                .map(|session| (token, session)),
+            None => None,
+        };
+
+        if let Some((token, session)) = session {
+            // Refresh the cookie so the browser's expiry slides along with the server's
+            cookies.add(session_cookie(token));
+            // Server functions behind this layer read the session via `leptos_axum::extract`
+            request.extensions_mut().insert(session);
             Ok(next.run(request).await)
         } else {
             log!("Unauthorized access attempt to a protected route.");
@@ -97,20 +216,65 @@ async fn main() {
         }
     }
 
+    // --- CSRF Middleware ---
+    // Layered inside `auth_middleware`, so the session is already in the request extensions.
+    async fn csrf_middleware(request: Request<Body>, next: Next) -> Result<Response, StatusCode> {
+        if request.method().is_safe() {
+            return Ok(next.run(request).await);
+        }
+
+        let expected = request.extensions().get::<Session>().map(|s| s.csrf_token.as_bytes());
+        let provided = request.headers().get(CSRF_HEADER).map(|v| v.as_bytes());
+        let valid = match (expected, provided) {
+            (Some(expected), Some(provided)) => {
+                !expected.is_empty() && bool::from(expected.ct_eq(provided))
+            }
+            _ => false,
+        };
+
+        if valid {
+            Ok(next.run(request).await)
+        } else {
+            log!("Rejected a request with a missing or invalid CSRF token.");
+            Err(StatusCode::FORBIDDEN)
+        }
+    }
+
     // --- API Handlers ---
 
     // Handler to publish a new blog post
     async fn publish_blog_post(
         State(db): State<Surreal<Db>>,
-        Json(payload): Json<BlogPost>,
+        Json(mut payload): Json<BlogPost>,
     ) -> Result<StatusCode, StatusCode> {
         log!("Received new blog post: {}", payload.title);
 
+        if payload.status == PublishStatus::Scheduled && payload.publish_at.is_none() {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+
+        payload.created_at = Datetime::default(); // now
+        payload.updated_at = payload.created_at.clone();
+        payload.slug = unique_slug(&db, "blog_post", &payload.title)
+            .await
+            .map_err(|e| {
+                log!("Failed to read from SurrealDB: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+
+        let tags = std::mem::take(&mut payload.tags);
         // Correct type annotation for the result of `db.create`
-        let created_post: Result<Option<BlogPost>, _> =
+        let created_post: Result<Option<CreatedRecord>, _> =
             db.create("blog_post").content(payload).await;
 
-        created_post.map_err(|e| {
+        let created_post = created_post
+            .map_err(|e| {
+                log!("Failed to write to SurrealDB: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        set_tags(&db, created_post.id, &tags).await.map_err(|e| {
             log!("Failed to write to SurrealDB: {:?}", e);
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
@@ -121,21 +285,74 @@ async fn main() {
     // Handler to publish a new project
     async fn publish_project(
         State(db): State<Surreal<Db>>,
-        Json(payload): Json<Project>,
+        Json(mut payload): Json<Project>,
     ) -> Result<StatusCode, StatusCode> {
         log!("Received new project: {}", payload.title);
 
+        if payload.status == PublishStatus::Scheduled && payload.publish_at.is_none() {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+
+        payload.created_at = Datetime::default(); // now
+        payload.updated_at = payload.created_at.clone();
+        payload.slug = unique_slug(&db, "project", &payload.title)
+            .await
+            .map_err(|e| {
+                log!("Failed to read from SurrealDB: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+
+        let tags = std::mem::take(&mut payload.tags);
         // Correct type annotation for the result of `db.create`
-        let created_project: Result<Option<Project>, _> =
+        let created_project: Result<Option<CreatedRecord>, _> =
             db.create("project").content(payload).await;
 
-        created_project.map_err(|e| {
+        let created_project = created_project
+            .map_err(|e| {
+                log!("Failed to write to SurrealDB: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        set_tags(&db, created_project.id, &tags).await.map_err(|e| {
             log!("Failed to write to SurrealDB: {:?}", e);
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
         Ok(StatusCode::CREATED)
     }
+    // Takes one image in the `file` field of a multipart form, see `shilohnova::media`
+    async fn upload_handler(
+        State(db): State<Surreal<Db>>,
+        mut multipart: Multipart,
+    ) -> Result<(StatusCode, Json<MediaRecord>), (StatusCode, String)> {
+        let bad_request = |e: axum::extract::multipart::MultipartError| (e.status(), e.body_text());
+        while let Some(mut field) = multipart.next_field().await.map_err(bad_request)? {
+            if field.name() != Some("file") {
+                continue;
+            }
+            let original_name = field.file_name().unwrap_or_default().to_string();
+            // Read in chunks, so an oversized file is turned away without buffering all of it
+            let mut bytes = Vec::new();
+            while let Some(chunk) = field.chunk().await.map_err(bad_request)? {
+                if bytes.len() + chunk.len() > media_max_bytes() {
+                    return Err((UploadError::TooLarge.status(), UploadError::TooLarge.message()));
+                }
+                bytes.extend_from_slice(&chunk);
+            }
+            return match store_upload(&db, &original_name, bytes).await {
+                Ok(media) => Ok((StatusCode::CREATED, Json(media))),
+                Err(e) => {
+                    if let UploadError::Storage(reason) = &e {
+                        log!("Failed to store upload: {}", reason);
+                    }
+                    Err((e.status(), e.message()))
+                }
+            };
+        }
+        Err((UploadError::Empty.status(), UploadError::Empty.message()))
+    }
+
     async fn server_fn_handler(
         State(app_state): State<AppState>,
         request: Request<Body>,
@@ -151,6 +368,33 @@ async fn main() {
             .await
     }
 
+    // Stylesheet for the classes `highlight_code` puts on code blocks
+    async fn highlight_css_handler() -> impl IntoResponse {
+        (
+            [(header::CONTENT_TYPE, "text/css; charset=utf-8")],
+            theme_css(),
+        )
+    }
+
+    // Feeds are built on each request from whatever is visible at the time
+    fn feed_route(kind: FeedKind, format: FeedFormat) -> axum::routing::MethodRouter<AppState> {
+        get(move |State(db): State<Surreal<Db>>, headers: HeaderMap| async move {
+            serve_feed(&db, &headers, kind, format).await
+        })
+    }
+
+    async fn og_image_handler(State(db): State<Surreal<Db>>, Path(file): Path<String>) -> Response {
+        serve_og_image(&db, &file).await
+    }
+
+    async fn sitemap_handler(State(db): State<Surreal<Db>>, headers: HeaderMap) -> Response {
+        serve_sitemap(&db, &headers).await
+    }
+
+    async fn robots_handler() -> impl IntoResponse {
+        robots_txt()
+    }
+
     // Serve the file system and handle errors
 
     async fn file_and_error_handler(
@@ -200,6 +444,47 @@ async fn main() {
     };
     log!("Connected to SurrealDB at: {}", "./data/shilohnova.db");
 
+    // Posts and projects created before slugs existed need one to be linked to, before the
+    // unique index on slugs goes on
+    for table in ["blog_post", "project"] {
+        if let Err(e) = backfill_slugs(&db, table).await {
+            log!("Failed to backfill slugs for {}: {}", table, e);
+            std::process::exit(1);
+        }
+    }
+
+    if let Err(e) = seed_home_sections(&db).await {
+        log!("Failed to create the home page sections: {}", e);
+        std::process::exit(1);
+    }
+
+    if let Err(e) = load_site_settings(&db).await {
+        log!("Failed to load the site settings: {}", e);
+        std::process::exit(1);
+    }
+
+    // Without it images still show, just without their resized copies
+    if let Err(e) = load_media_index(&db).await {
+        log!("Failed to load the media library: {}", e);
+    }
+
+    // Mail is sent in the background; a broken SMTP setup shouldn't keep the site down
+    match mailer_from_env() {
+        Ok(mailer) => start_mailer(mailer),
+        Err(e) => log!("Failed to set up outgoing mail, none will be sent: {}", e),
+    }
+
+    // Create the first admin account from the environment, if one was given
+    if let Err(e) = bootstrap_admin(&db).await {
+        log!("Failed to create the admin account: {}", e);
+        std::process::exit(1);
+    }
+
+    if let Err(e) = init_dummy_hash().await {
+        log!("Failed to prepare password checks: {}", e);
+        std::process::exit(1);
+    }
+
     // Create the shared application state
     let app_state = AppState {
         leptos_options: leptos_options.clone(),
@@ -214,6 +499,13 @@ async fn main() {
     let protected_routes = Router::new()
         .route("/api/publish-blog", post(publish_blog_post))
         .route("/api/publish-project", post(publish_project))
+        .route(
+            "/api/admin/upload",
+            // Room for the multipart framing around the largest file allowed
+            post(upload_handler).layer(DefaultBodyLimit::max(media_max_bytes() + 64 * 1024)),
+        )
+        // Behind the CSRF check too, so other sites can't sign the admin out
+        .route("/api/admin/logout", post(logout_handler))
         .route("/api/admin/{*fn_name}", post(server_fn_handler))
         .route(
             "/adminpanel",
@@ -225,16 +517,77 @@ async fn main() {
                 move || shell(leptos_options_clone_for_shell_admin.clone()),
             )),
         )
+        .layer(middleware::from_fn(csrf_middleware))
         .layer(middleware::from_fn_with_state(
             app_state.clone(),
             auth_middleware,
         ));
 
+    // Per-IP request limits; failed logins are locked out separately in `login_handler`
+    let login_limiter = RateLimiter::new(5, 10);
+    let contact_limiter = RateLimiter::new(3, 1);
+    let password_reset_limiter = RateLimiter::new(5, 2);
+    let public_api_limiter = RateLimiter::new(30, 120);
+
     // Create a router for the public routes
     let mut public_routes = Router::new()
-        .route("/api/login", post(login_handler))
-        .route("/api/{*fn_name}", post(server_fn_handler));
+        .route(
+            "/api/login",
+            post(login_handler).layer(middleware::from_fn_with_state(
+                login_limiter,
+                rate_limit_middleware,
+            )),
+        )
+        // `SubmitContactMessage`, limited more tightly than the other server functions
+        .route(
+            "/api/contact",
+            post(server_fn_handler).layer(middleware::from_fn_with_state(
+                contact_limiter,
+                rate_limit_middleware,
+            )),
+        )
+        // `RequestPasswordReset` sends mail and `ResetPassword` hashes a password, so both are
+        // kept to a trickle
+        .route(
+            "/api/forgot-password",
+            post(server_fn_handler).layer(middleware::from_fn_with_state(
+                password_reset_limiter.clone(),
+                rate_limit_middleware,
+            )),
+        )
+        .route(
+            "/api/reset-password",
+            post(server_fn_handler).layer(middleware::from_fn_with_state(
+                password_reset_limiter.clone(),
+                rate_limit_middleware,
+            )),
+        )
+        // `VerifyEmail` takes the same kind of token, so it gets the same limit
+        .route(
+            "/api/verify-email",
+            post(server_fn_handler).layer(middleware::from_fn_with_state(
+                password_reset_limiter,
+                rate_limit_middleware,
+            )),
+        )
+        .route("/pkg/highlight.css", get(highlight_css_handler))
+        .route("/og/{file}", get(og_image_handler))
+        .route("/sitemap.xml", get(sitemap_handler))
+        .route("/robots.txt", get(robots_handler))
+        .nest_service("/media", ServeDir::new(media_dir()))
+        .route(
+            "/api/{*fn_name}",
+            post(server_fn_handler).layer(middleware::from_fn_with_state(
+                public_api_limiter,
+                rate_limit_middleware,
+            )),
+        );
 
+    for kind in [FeedKind::Views, FeedKind::Projects] {
+        for format in [FeedFormat::Rss, FeedFormat::Atom, FeedFormat::Json] {
+            public_routes = public_routes.route(kind.feed_path(format), feed_route(kind, format));
+        }
+    }
 
     for route in routes
         .into_iter()
@@ -243,19 +596,21 @@ async fn main() {
         let leptos_options_clone_for_context = app_state.leptos_options.clone();
         let leptos_options_clone_for_shell = app_state.leptos_options.clone();
         let db_clone_for_context = app_state.db.clone();
-        public_routes = public_routes.route(
-            route.path(),
-            get(render_app_to_stream_with_context(
-                // We provide the database connection here, so the server functions can access it.
-                move || {
-                    let leptos_options = leptos_options_clone_for_context.clone();
-                    // Provide the database connection as a context
-                    provide_context(db_clone_for_context.clone());
-                    provide_context(leptos_options);
-                },
-                move || shell(leptos_options_clone_for_shell.clone()),
-            )),
-        );
+        // We provide the database connection here, so the server functions can access it.
+        let context = move || {
+            let leptos_options = leptos_options_clone_for_context.clone();
+            // Provide the database connection as a context
+            provide_context(db_clone_for_context.clone());
+            provide_context(leptos_options);
+        };
+        let app_fn = move || shell(leptos_options_clone_for_shell.clone());
+        // Async routes wait for their data before sending anything, so they can set the status code
+        let handler = if *route.mode() == SsrMode::Async {
+            get(render_app_async_with_context(context, app_fn))
+        } else {
+            get(render_app_to_stream_with_context(context, app_fn))
+        };
+        public_routes = public_routes.route(route.path(), handler);
     }
 
     // Combine the routers, add state, fallback, and final layers
@@ -268,7 +623,7 @@ async fn main() {
 
     log!("listening on http://{}", &addr);
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app.into_make_service())
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .unwrap();
 }
\ No newline at end of file
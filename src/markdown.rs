@@ -0,0 +1,144 @@
+// Markdown rendering for post and project content. Runs on the server only, so neither
+// the parser nor the sanitizer ends up in the WASM bundle.
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+use ammonia::Builder;
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+
+use crate::highlight::highlight_code;
+use crate::media::{picture_html, responsive_image};
+
+// Content is at most this wide (the `max-w-3xl` column), so browsers needn't fetch more
+const CONTENT_IMAGE_SIZES: &str = "(min-width: 768px) 768px, 100vw";
+
+// CommonMark plus the GitHub extensions we use.
+fn options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_FOOTNOTES
+}
+
+// Content is written by admins, but it is still sanitized so a pasted snippet can't
+// run script on the site. Task list checkboxes are the only inputs allowed through,
+// only the highlighter's own classes survive on code, and images keep what `srcset` needs.
+fn sanitizer() -> &'static Builder<'static> {
+    static SANITIZER: OnceLock<Builder<'static>> = OnceLock::new();
+    SANITIZER.get_or_init(|| {
+        let mut builder = Builder::default();
+        builder
+            .add_tags(["input", "picture", "source"])
+            .add_tag_attributes("input", ["type", "checked", "disabled"])
+            .add_tag_attributes("source", ["type", "srcset", "sizes"])
+            .add_tag_attributes("img", ["srcset", "sizes", "width", "height", "loading", "decoding"])
+            .add_tag_attributes("pre", ["class"])
+            .add_tag_attributes("span", ["class"])
+            .attribute_filter(|element, attribute, value| match (element, attribute) {
+                ("input", "type") if value != "checkbox" => None,
+                (_, "class") => {
+                    let classes: Vec<&str> = value
+                        .split_whitespace()
+                        .filter(|class| class.starts_with("hl-"))
+                        .collect();
+                    Some(Cow::Owned(classes.join(" ")))
+                }
+                _ => Some(Cow::Borrowed(value)),
+            });
+        builder
+    })
+}
+
+// Replaces each fenced code block with its highlighted HTML.
+fn highlight_code_blocks<'a>(events: impl Iterator<Item = Event<'a>>) -> Vec<Event<'a>> {
+    let mut output = Vec::new();
+    let mut code_block: Option<(CowStr<'a>, String)> = None;
+
+    for event in events {
+        match (event, &mut code_block) {
+            (Event::Start(Tag::CodeBlock(kind)), None) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => info,
+                    CodeBlockKind::Indented => CowStr::Borrowed(""),
+                };
+                code_block = Some((language, String::new()));
+            }
+            (Event::Text(text), Some((_, code))) => code.push_str(&text),
+            (Event::End(TagEnd::CodeBlock), Some((info, code))) => {
+                // The info string can carry more than the language, e.g. "rust,ignore"
+                let language = info.split([' ', ',']).next().unwrap_or_default();
+                output.push(Event::Html(highlight_code(language, code).into()));
+                code_block = None;
+            }
+            (event, _) => output.push(event),
+        }
+    }
+    output
+}
+
+// Replaces images from the media library with a `<picture>` offering their resized and
+// WebP copies. Other images are left as they are.
+fn responsive_images<'a>(events: impl Iterator<Item = Event<'a>>) -> Vec<Event<'a>> {
+    let mut output = Vec::new();
+    let mut image: Option<(CowStr<'a>, CowStr<'a>, String)> = None;
+
+    for event in events {
+        match (event, &mut image) {
+            (Event::Start(Tag::Image { dest_url, title, .. }), None) if dest_url.starts_with("/media/") => {
+                image = Some((dest_url, title, String::new()));
+            }
+            // The alt text is the plain text of whatever the image's brackets hold
+            (Event::Text(text) | Event::Code(text), Some((_, _, alt))) => alt.push_str(&text),
+            (Event::End(TagEnd::Image), Some((url, title, alt))) => {
+                let picture = picture_html(&responsive_image(url), alt, title, CONTENT_IMAGE_SIZES);
+                output.push(Event::InlineHtml(picture.into()));
+                image = None;
+            }
+            (_, Some(_)) => {}
+            (event, None) => output.push(event),
+        }
+    }
+    output
+}
+
+// Renders Markdown to sanitized HTML, ready for `inner_html`.
+pub fn render_markdown(source: &str) -> String {
+    let mut unsafe_html = String::with_capacity(source.len() * 3 / 2);
+    let events = highlight_code_blocks(Parser::new_ext(source, options()));
+    let events = responsive_images(events.into_iter());
+    html::push_html(&mut unsafe_html, events.into_iter());
+    sanitizer().clean(&unsafe_html).to_string()
+}
+
+// The text of the Markdown without any formatting, for excerpts and descriptions.
+pub fn plain_text(source: &str) -> String {
+    let mut text = String::with_capacity(source.len());
+    for event in Parser::new_ext(source, options()) {
+        match event {
+            Event::Text(t) | Event::Code(t) => text.push_str(&t),
+            Event::SoftBreak | Event::HardBreak | Event::End(_) => text.push(' '),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// The first `max_chars` characters of the plain text, cut at a word boundary.
+pub fn excerpt(source: &str, max_chars: usize) -> String {
+    let text = plain_text(source);
+    match text.char_indices().nth(max_chars) {
+        None => text,
+        Some((end, _)) => {
+            let cut = text[..end].rfind(char::is_whitespace).unwrap_or(end);
+            format!("{}…", text[..cut].trim_end())
+        }
+    }
+}
+
+// The address of the first image in the Markdown, shown in link previews.
+pub fn first_image(source: &str) -> Option<String> {
+    Parser::new_ext(source, options()).find_map(|event| match event {
+        Event::Start(Tag::Image { dest_url, .. }) if !dest_url.is_empty() => Some(dest_url.to_string()),
+        _ => None,
+    })
+}
@@ -0,0 +1,463 @@
+// Images uploaded from the admin panel. Each upload is named after a hash of its bytes, so
+// uploading the same image twice keeps one copy, and a `media:<hash>` record holds what the
+// admin panel shows about it.
+//
+// Uploads are decoded and encoded again before they are stored, which drops EXIF (GPS
+// included) and other metadata, and resized copies are made at `VARIANT_WIDTHS` along with
+// WebP versions of each, for `srcset`. What was made for each image is kept in memory too, so
+// rendering Markdown, which can't wait on the database, can still write out a `srcset`.
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::{OnceLock, RwLock};
+
+use axum::http::StatusCode;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+use leptos::logging::log;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Datetime;
+use surrealdb::Surreal;
+
+use crate::app::{MediaFromDB, MediaRecord, ResponsiveImage};
+use crate::config::{media_dir, media_max_bytes};
+
+// Widths of the resized copies, for phones up to large screens. Only those narrower than
+// the upload are made.
+const VARIANT_WIDTHS: [u32; 4] = [320, 640, 960, 1280];
+const JPEG_QUALITY: u8 = 85;
+// Anything bigger is more likely a decompression bomb than a photo
+const MAX_DIMENSION: u32 = 12_000;
+
+#[derive(Debug)]
+pub enum UploadError {
+    Empty,
+    TooLarge,
+    UnsupportedType,
+    Unreadable,
+    Storage(String),
+}
+
+impl UploadError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            UploadError::Empty => StatusCode::BAD_REQUEST,
+            UploadError::TooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            UploadError::UnsupportedType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            UploadError::Unreadable => StatusCode::UNPROCESSABLE_ENTITY,
+            UploadError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    // Shown in the admin panel, so storage errors are left to the log
+    pub fn message(&self) -> String {
+        match self {
+            UploadError::Empty => "No file was uploaded.".to_string(),
+            UploadError::TooLarge => format!("Files can be at most {} MiB.", media_max_bytes() / (1024 * 1024)),
+            UploadError::UnsupportedType => "Only PNG, JPEG, GIF and WebP images can be uploaded.".to_string(),
+            UploadError::Unreadable => "The image couldn't be read; it may be damaged or too large.".to_string(),
+            UploadError::Storage(_) => "The file couldn't be stored.".to_string(),
+        }
+    }
+}
+
+// --- Image Info ---
+// What was made from an upload: `<hash>.<ext>`, `<hash>-<width>.<ext>` for each variant, and
+// the same again with a `.webp` extension if `webp`.
+#[derive(Debug, Clone)]
+pub struct ImageInfo {
+    hash: String,
+    extension: String,
+    width: Option<u32>,
+    height: Option<u32>,
+    variant_widths: Vec<u32>,
+    webp: bool,
+}
+
+impl From<&MediaFromDB> for ImageInfo {
+    fn from(m: &MediaFromDB) -> Self {
+        let (hash, extension) = m.file_name.split_once('.').unwrap_or((&m.file_name, ""));
+        ImageInfo {
+            hash: hash.to_string(),
+            extension: extension.to_string(),
+            width: m.width,
+            height: m.height,
+            variant_widths: m.variant_widths.clone(),
+            webp: m.webp,
+        }
+    }
+}
+
+impl ImageInfo {
+    fn url(&self, width: Option<u32>, extension: &str) -> String {
+        match width {
+            Some(width) => format!("/media/{}-{}.{}", self.hash, width, extension),
+            None => format!("/media/{}.{}", self.hash, extension),
+        }
+    }
+
+    // Every copy in one format, the full-size image last.
+    fn srcset(&self, extension: &str) -> String {
+        let Some(full_width) = self.width else {
+            return String::new();
+        };
+        self.variant_widths
+            .iter()
+            .map(|&width| format!("{} {}w", self.url(Some(width), extension), width))
+            .chain(std::iter::once(format!("{} {}w", self.url(None, extension), full_width)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn thumbnail_url(&self) -> String {
+        self.url(self.variant_widths.first().copied(), &self.extension)
+    }
+
+    pub fn responsive(&self) -> ResponsiveImage {
+        ResponsiveImage {
+            src: self.url(None, &self.extension),
+            srcset: self.srcset(&self.extension),
+            webp_srcset: if self.webp { self.srcset("webp") } else { String::new() },
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+fn index() -> &'static RwLock<HashMap<String, ImageInfo>> {
+    static INDEX: OnceLock<RwLock<HashMap<String, ImageInfo>>> = OnceLock::new();
+    INDEX.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+fn remember(info: ImageInfo) {
+    let file_name = format!("{}.{}", info.hash, info.extension);
+    if let Ok(mut index) = index().write() {
+        index.insert(file_name, info);
+    }
+}
+
+// Fills the in-memory index from the `media` table. Called once on start-up.
+//
+// Images uploaded before resizing was added (those without a `width`) are processed now, from
+// the file on disk, so they lose their EXIF data and get their copies like any new upload.
+pub async fn load_media_index(db: &Surreal<Db>) -> Result<(), surrealdb::Error> {
+    let media: Vec<MediaFromDB> = db.select("media").await?;
+    for m in media {
+        if m.width.is_some() {
+            remember(ImageInfo::from(&m));
+            continue;
+        }
+        let path = media_dir().join(&m.file_name);
+        let processed = match tokio::fs::read(&path).await {
+            Ok(bytes) => reprocess(db, m.clone(), bytes).await,
+            Err(e) => Err(UploadError::Storage(format!("{}: {}", path.display(), e))),
+        };
+        if let Err(e) = processed {
+            log!("Failed to process {}: {:?}", m.file_name, e);
+            remember(ImageInfo::from(&m));
+        }
+    }
+    Ok(())
+}
+
+// The image at `url`, with its resized copies if it's in the media library.
+pub fn responsive_image(url: &str) -> ResponsiveImage {
+    let known = url
+        .strip_prefix("/media/")
+        .and_then(|file_name| index().read().ok()?.get(file_name).cloned());
+    match known {
+        Some(info) => info.responsive(),
+        None => ResponsiveImage {
+            src: url.to_string(),
+            srcset: String::new(),
+            webp_srcset: String::new(),
+            width: None,
+            height: None,
+        },
+    }
+}
+
+// --- Processing ---
+// The type of an image from its first bytes, as (extension, content type). What the browser
+// claims the file is doesn't matter; what we serve it as does.
+fn sniff_image(bytes: &[u8]) -> Option<(&'static str, &'static str)> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(("png", "image/png"))
+    } else if bytes.starts_with(b"\xff\xd8\xff") {
+        Some(("jpg", "image/jpeg"))
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some(("gif", "image/gif"))
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some(("webp", "image/webp"))
+    } else {
+        None
+    }
+}
+
+fn reader(bytes: &[u8]) -> Result<ImageReader<Cursor<&[u8]>>, UploadError> {
+    let mut reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|_| UploadError::Unreadable)?;
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    reader.limits(limits);
+    Ok(reader)
+}
+
+fn encode(image: &DynamicImage, extension: &str) -> Result<Vec<u8>, UploadError> {
+    let mut bytes = Vec::new();
+    let result = match extension {
+        "jpg" => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY)),
+        "webp" => DynamicImage::ImageRgba8(image.to_rgba8()).write_with_encoder(WebPEncoder::new_lossless(&mut bytes)),
+        _ => image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png),
+    };
+    result.map_err(|e| UploadError::Storage(e.to_string()))?;
+    Ok(bytes)
+}
+
+// Files to write to the media directory, as (file name, bytes)
+type Files = Vec<(String, Vec<u8>)>;
+
+// Decodes the upload and makes every file for it.
+fn process_image(hash: &str, extension: &str, bytes: &[u8]) -> Result<(ImageInfo, Files), UploadError> {
+    let mut info = ImageInfo {
+        hash: hash.to_string(),
+        extension: extension.to_string(),
+        width: None,
+        height: None,
+        variant_widths: Vec::new(),
+        webp: false,
+    };
+
+    // GIFs are kept as they are, since re-encoding or resizing would lose any animation.
+    // They can't carry EXIF data anyway.
+    if extension == "gif" {
+        let (width, height) = reader(bytes)?.into_dimensions().map_err(|_| UploadError::Unreadable)?;
+        info.width = Some(width);
+        info.height = Some(height);
+        return Ok((info, vec![(format!("{}.gif", hash), bytes.to_vec())]));
+    }
+
+    // Phone photos are often stored sideways with an EXIF tag saying which way is up, so the
+    // rotation is applied before the tag is dropped
+    let mut decoder = reader(bytes)?.into_decoder().map_err(|_| UploadError::Unreadable)?;
+    let orientation = decoder.orientation().map_err(|_| UploadError::Unreadable)?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|_| UploadError::Unreadable)?;
+    image.apply_orientation(orientation);
+    info.width = Some(image.width());
+    info.height = Some(image.height());
+
+    let mut sized = vec![(None, image.clone())];
+    for width in VARIANT_WIDTHS.into_iter().filter(|&width| width < image.width()) {
+        sized.push((Some(width), image.resize(width, u32::MAX, FilterType::Lanczos3)));
+        info.variant_widths.push(width);
+    }
+
+    let mut files = Vec::new();
+    for (width, copy) in &sized {
+        files.push((file_name(hash, *width, extension), encode(copy, extension)?));
+    }
+
+    // The WebP encoder only does lossless, which beats PNG but usually loses to JPEG, so the
+    // WebP copies are only kept if they save something overall
+    if extension != "webp" {
+        let mut webp_files = Vec::new();
+        for (width, copy) in &sized {
+            webp_files.push((file_name(hash, *width, "webp"), encode(copy, "webp")?));
+        }
+        let original_size: usize = files.iter().map(|(_, bytes)| bytes.len()).sum();
+        let webp_size: usize = webp_files.iter().map(|(_, bytes)| bytes.len()).sum();
+        if webp_size < original_size {
+            info.webp = true;
+            files.extend(webp_files);
+        }
+    }
+
+    Ok((info, files))
+}
+
+fn file_name(hash: &str, width: Option<u32>, extension: &str) -> String {
+    match width {
+        Some(width) => format!("{}-{}.{}", hash, width, extension),
+        None => format!("{}.{}", hash, extension),
+    }
+}
+
+// Existing files are replaced, since an image from before processing was added is stored
+// under the same name as its processed copy.
+fn write_files(dir: &Path, files: &Files) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for (file_name, bytes) in files {
+        let path = dir.join(file_name);
+        // Written under a temporary name first, so a half-written file is never served
+        let partial = dir.join(format!(".{}.partial", file_name));
+        std::fs::write(&partial, bytes)?;
+        std::fs::rename(&partial, &path)?;
+    }
+    Ok(())
+}
+
+// Checks and stores an upload, returning the new (or existing, for a repeat) library entry.
+pub async fn store_upload(db: &Surreal<Db>, original_name: &str, bytes: Vec<u8>) -> Result<MediaRecord, UploadError> {
+    #[derive(Serialize)]
+    struct NewMedia {
+        file_name: String,
+        original_name: String,
+        content_type: String,
+        size: u64,
+        width: Option<u32>,
+        height: Option<u32>,
+        variant_widths: Vec<u32>,
+        webp: bool,
+        created_at: Datetime,
+    }
+
+    if bytes.is_empty() {
+        return Err(UploadError::Empty);
+    }
+    if bytes.len() > media_max_bytes() {
+        return Err(UploadError::TooLarge);
+    }
+    let (extension, content_type) = sniff_image(&bytes).ok_or(UploadError::UnsupportedType)?;
+
+    let hash = hex::encode(Sha256::digest(&bytes))[..32].to_string();
+    let existing: Option<MediaFromDB> = db
+        .select(("media", hash.as_str()))
+        .await
+        .map_err(|e| UploadError::Storage(e.to_string()))?;
+    if let Some(existing) = existing {
+        // Stored as uploaded, before processing was added
+        if existing.width.is_none() {
+            return reprocess(db, existing, bytes).await;
+        }
+        return Ok(existing.into());
+    }
+
+    let (info, size) = process_and_write(hash.clone(), extension, bytes).await?;
+
+    let created: Option<MediaFromDB> = db
+        .create(("media", hash.as_str()))
+        .content(NewMedia {
+            file_name: file_name(&hash, None, extension),
+            original_name: clean_name(original_name),
+            content_type: content_type.to_string(),
+            size,
+            width: info.width,
+            height: info.height,
+            variant_widths: info.variant_widths.clone(),
+            webp: info.webp,
+            created_at: Datetime::default(), // now
+        })
+        .await
+        .map_err(|e| UploadError::Storage(e.to_string()))?;
+    let created = created.ok_or_else(|| UploadError::Storage("the media record wasn't created".to_string()))?;
+    remember(info);
+    Ok(created.into())
+}
+
+// Processes an image and writes its files, returning what was made and the size of the
+// full-size copy.
+async fn process_and_write(hash: String, extension: &'static str, bytes: Vec<u8>) -> Result<(ImageInfo, u64), UploadError> {
+    tokio::task::spawn_blocking(move || -> Result<(ImageInfo, u64), UploadError> {
+        let (info, files) = process_image(&hash, extension, &bytes)?;
+        // The size shown is that of the stored file, which lost its metadata
+        let size = files.first().map_or(0, |(_, bytes)| bytes.len() as u64);
+        write_files(&media_dir(), &files).map_err(|e| UploadError::Storage(e.to_string()))?;
+        Ok((info, size))
+    })
+    .await
+    .map_err(|e| UploadError::Storage(e.to_string()))?
+}
+
+// Processes an image stored before processing was added, replacing the file as uploaded, and
+// fills in its record.
+async fn reprocess(db: &Surreal<Db>, media: MediaFromDB, bytes: Vec<u8>) -> Result<MediaRecord, UploadError> {
+    #[derive(Serialize)]
+    struct Processed {
+        size: u64,
+        width: Option<u32>,
+        height: Option<u32>,
+        variant_widths: Vec<u32>,
+        webp: bool,
+    }
+
+    let (extension, _) = sniff_image(&bytes).ok_or(UploadError::UnsupportedType)?;
+    let hash = media.id.id.to_raw();
+    let (info, size) = process_and_write(hash.clone(), extension, bytes).await?;
+
+    let updated: Option<MediaFromDB> = db
+        .update(("media", hash.as_str()))
+        .merge(Processed {
+            size,
+            width: info.width,
+            height: info.height,
+            variant_widths: info.variant_widths.clone(),
+            webp: info.webp,
+        })
+        .await
+        .map_err(|e| UploadError::Storage(e.to_string()))?;
+    let updated = updated.ok_or_else(|| UploadError::Storage("the media record wasn't found".to_string()))?;
+    remember(info);
+    Ok(updated.into())
+}
+
+// Keeps just the file name from what the browser sent, for display only.
+fn clean_name(original_name: &str) -> String {
+    let name = original_name.rsplit(['/', '\\']).next().unwrap_or_default().trim();
+    let name: String = name.chars().filter(|c| !c.is_control()).take(200).collect();
+    if name.is_empty() {
+        "image".to_string()
+    } else {
+        name
+    }
+}
+
+// --- HTML ---
+fn push_attribute(html: &mut String, name: &str, value: &str) {
+    html.push(' ');
+    html.push_str(name);
+    html.push_str("=\"");
+    for c in value.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            c => html.push(c),
+        }
+    }
+    html.push('"');
+}
+
+// The same markup as the `Picture` component, for images in rendered Markdown.
+pub fn picture_html(image: &ResponsiveImage, alt: &str, title: &str, sizes: &str) -> String {
+    let mut html = String::from("<picture>");
+    if !image.webp_srcset.is_empty() {
+        html.push_str("<source type=\"image/webp\"");
+        push_attribute(&mut html, "srcset", &image.webp_srcset);
+        push_attribute(&mut html, "sizes", sizes);
+        html.push('>');
+    }
+    html.push_str("<img");
+    push_attribute(&mut html, "src", &image.src);
+    if !image.srcset.is_empty() {
+        push_attribute(&mut html, "srcset", &image.srcset);
+        push_attribute(&mut html, "sizes", sizes);
+    }
+    if let (Some(width), Some(height)) = (image.width, image.height) {
+        push_attribute(&mut html, "width", &width.to_string());
+        push_attribute(&mut html, "height", &height.to_string());
+    }
+    push_attribute(&mut html, "alt", alt);
+    if !title.is_empty() {
+        push_attribute(&mut html, "title", title);
+    }
+    html.push_str(" loading=\"lazy\" decoding=\"async\"></picture>");
+    html
+}
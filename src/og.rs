@@ -0,0 +1,250 @@
+// Social preview cards for posts, served at `/og/<slug>.png`: the post title and the site
+// name in the site's colours, drawn with a bundled font so the result doesn't depend on what
+// is installed on the server. Each card is written to `og_cache_dir()` the first time it is
+// asked for, under a name that includes the post's `updated_at` and a hash of the site title,
+// so editing the post or renaming the site makes the next request draw a fresh one.
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use ab_glyph::{point, Font, FontRef, PxScale, ScaleFont};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use image::{ImageFormat, Rgb, RgbImage};
+use leptos::logging::log;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use surrealdb::engine::local::Db;
+use surrealdb::Surreal;
+use time::OffsetDateTime;
+
+use crate::app::PUBLICLY_VISIBLE;
+use crate::config::og_cache_dir;
+use crate::settings::site_settings;
+
+// DejaVu Sans Bold; see LICENSES.md
+static FONT: &[u8] = include_bytes!("../assets/fonts/DejaVuSans-Bold.ttf");
+
+const WIDTH: u32 = 1200;
+const HEIGHT: u32 = 630;
+const MARGIN: f32 = 80.0;
+const FOOTER_HEIGHT: u32 = 120;
+
+// The `sandy-beige`, `navy-blue-custom` and `charcoal-custom` colours from input.css
+const BACKGROUND: Rgb<u8> = Rgb([0x2d, 0x2d, 0x0d]);
+const FOREGROUND: Rgb<u8> = Rgb([0x7f, 0xae, 0xff]);
+const FOOTER: Rgb<u8> = Rgb([0x36, 0x45, 0x4f]);
+
+// Long titles are set smaller rather than cut short, down to the last size here.
+const TITLE_SIZES: [f32; 3] = [84.0, 68.0, 56.0];
+const TITLE_MAX_LINES: usize = 4;
+
+#[derive(Debug, Deserialize)]
+struct OgPost {
+    title: String,
+    #[serde(with = "time::serde::rfc3339")]
+    updated_at: OffsetDateTime,
+}
+
+// --- Drawing ---
+fn text_width(font: &FontRef<'static>, scale: PxScale, text: &str) -> f32 {
+    let font = font.as_scaled(scale);
+    let mut width = 0.0;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        if let Some(previous) = previous {
+            width += font.kern(previous, id);
+        }
+        width += font.h_advance(id);
+        previous = Some(id);
+    }
+    width
+}
+
+// Breaks the text into lines no wider than `max_width`. A single word too long for a line
+// gets a line of its own and is left to overflow.
+fn wrap(font: &FontRef<'static>, scale: PxScale, text: &str, max_width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+        if line.is_empty() || text_width(font, scale, &candidate) <= max_width {
+            line = candidate;
+        } else {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+// The title at the largest size it fits at, ending in an ellipsis if even the smallest is too big.
+fn layout_title(font: &FontRef<'static>, title: &str, max_width: f32) -> (PxScale, Vec<String>) {
+    for size in TITLE_SIZES {
+        let scale = PxScale::from(size);
+        let lines = wrap(font, scale, title, max_width);
+        if lines.len() <= TITLE_MAX_LINES || size == TITLE_SIZES[TITLE_SIZES.len() - 1] {
+            let mut lines = lines;
+            if lines.len() > TITLE_MAX_LINES {
+                lines.truncate(TITLE_MAX_LINES);
+                let last = &mut lines[TITLE_MAX_LINES - 1];
+                while !last.is_empty() && text_width(font, scale, &format!("{}…", last)) > max_width {
+                    last.pop();
+                }
+                *last = format!("{}…", last.trim_end());
+            }
+            return (scale, lines);
+        }
+    }
+    unreachable!("the last size always returns")
+}
+
+// Draws one line of text with its baseline at `y`, blending it into the background.
+fn draw_text(image: &mut RgbImage, font: &FontRef<'static>, scale: PxScale, x: f32, y: f32, text: &str, colour: Rgb<u8>) {
+    let scaled = font.as_scaled(scale);
+    let mut caret = x;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(previous) = previous {
+            caret += scaled.kern(previous, id);
+        }
+        let glyph = id.with_scale_and_position(scale, point(caret, y));
+        caret += scaled.h_advance(id);
+        previous = Some(id);
+
+        let Some(outline) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outline.px_bounds();
+        outline.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i32 + gx as i32;
+            let py = bounds.min.y as i32 + gy as i32;
+            if px < 0 || py < 0 || px >= WIDTH as i32 || py >= HEIGHT as i32 {
+                return;
+            }
+            let pixel = image.get_pixel_mut(px as u32, py as u32);
+            for channel in 0..3 {
+                let under = pixel[channel] as f32;
+                let over = colour[channel] as f32;
+                pixel[channel] = (under + (over - under) * coverage.min(1.0)).round() as u8;
+            }
+        });
+    }
+}
+
+fn render_card(title: &str, site_title: &str) -> Result<Vec<u8>, String> {
+    let font = FontRef::try_from_slice(FONT).map_err(|e| e.to_string())?;
+    let mut image = RgbImage::from_pixel(WIDTH, HEIGHT, BACKGROUND);
+
+    // Footer band with the site name
+    for y in HEIGHT - FOOTER_HEIGHT..HEIGHT {
+        for x in 0..WIDTH {
+            image.put_pixel(x, y, FOOTER);
+        }
+    }
+    let footer_scale = PxScale::from(40.0);
+    let footer_baseline = (HEIGHT - FOOTER_HEIGHT) as f32 + (FOOTER_HEIGHT as f32 + font.as_scaled(footer_scale).ascent() * 0.7) / 2.0;
+    draw_text(&mut image, &font, footer_scale, MARGIN, footer_baseline, site_title, FOREGROUND);
+
+    // The title, centred vertically in the space above the footer
+    let (scale, lines) = layout_title(&font, title, WIDTH as f32 - 2.0 * MARGIN);
+    let scaled = font.as_scaled(scale);
+    let line_height = scaled.height() + scaled.line_gap();
+    let block_height = line_height * lines.len() as f32;
+    let mut baseline = ((HEIGHT - FOOTER_HEIGHT) as f32 - block_height) / 2.0 + scaled.ascent();
+    for line in &lines {
+        draw_text(&mut image, &font, scale, MARGIN, baseline, line, FOREGROUND);
+        baseline += line_height;
+    }
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok(png)
+}
+
+// --- Cache ---
+fn cache_path(dir: &Path, slug: &str, updated_at: OffsetDateTime, site_title: &str) -> PathBuf {
+    let site_hash = &hex::encode(Sha256::digest(site_title.as_bytes()))[..8];
+    // Slugs never contain dots, so `<slug>.` only ever matches this post's cards
+    dir.join(format!("{}.{}.{}.png", slug, updated_at.unix_timestamp_nanos(), site_hash))
+}
+
+// Reads the card from the cache, or draws and stores it, removing cards of older versions.
+fn load_or_render(dir: &Path, slug: &str, post: &OgPost, site_title: &str) -> Result<Vec<u8>, String> {
+    let path = cache_path(dir, slug, post.updated_at, site_title);
+    if let Ok(png) = std::fs::read(&path) {
+        return Ok(png);
+    }
+
+    let png = render_card(&post.title, site_title)?;
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        let prefix = format!("{}.", slug);
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+    // A failed write only costs drawing it again next time
+    if let Err(e) = std::fs::write(&path, &png) {
+        log!("Failed to cache preview image {}: {}", path.display(), e);
+    }
+    Ok(png)
+}
+
+async fn find_post(db: &Surreal<Db>, slug: &str) -> Result<Option<OgPost>, surrealdb::Error> {
+    let mut response = db
+        .query(format!(
+            "SELECT title, updated_at FROM blog_post WHERE slug = $slug AND {PUBLICLY_VISIBLE} LIMIT 1"
+        ))
+        .bind(("slug", slug.to_string()))
+        .await?;
+    response.take(0)
+}
+
+// Handles `/og/<file>`, where `file` is `<slug>.png` for a publicly visible post.
+pub async fn serve_og_image(db: &Surreal<Db>, file: &str) -> Response {
+    let Some(slug) = file.strip_suffix(".png") else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let post = match find_post(db, slug).await {
+        Ok(post) => post,
+        Err(e) => {
+            log!("Failed to read from SurrealDB: {:?}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let Some(post) = post else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let slug = slug.to_string();
+    let site_title = site_settings().site_title;
+    let rendered =
+        tokio::task::spawn_blocking(move || load_or_render(&og_cache_dir(), &slug, &post, &site_title)).await;
+    match rendered {
+        Ok(Ok(png)) => (
+            [
+                (header::CONTENT_TYPE, "image/png"),
+                (header::CACHE_CONTROL, "public, max-age=3600"),
+            ],
+            png,
+        )
+            .into_response(),
+        Ok(Err(e)) => {
+            log!("Failed to draw preview image: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+        Err(e) => {
+            log!("Preview image task failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
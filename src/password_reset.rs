@@ -0,0 +1,288 @@
+// Resetting a forgotten password by email, from `/login/forgot` and `/login/reset/<token>`,
+// and confirming that an account's email address is really its owner's, from
+// `/login/verify/<token>`.
+//
+// A reset link carries a random token; only its SHA-256 is kept, in the `password_reset`
+// table, like session tokens. Each token works once and only for `RESET_TOKEN_TTL`, and
+// asking for a new one replaces any the account already had. Setting a new password signs the
+// account out everywhere. Verification links work the same way, from the
+// `email_verification` table, and set the account's `email_verified_at`.
+use std::time::Duration;
+
+use leptos::logging::log;
+use serde::Deserialize;
+use surrealdb::engine::local::Db;
+use surrealdb::sql::{Datetime, Thing};
+use surrealdb::Surreal;
+
+use crate::auth::{
+    account_throttle_key, clear_login_failures, delete_user_sessions, find_user_by_email, generate_token, hash_password,
+    hash_token,
+};
+use crate::config::site_url;
+use crate::mail::{queue_email, Email};
+use crate::settings::site_settings;
+
+pub const RESET_TOKEN_TTL: Duration = Duration::from_secs(60 * 60);
+pub const VERIFY_TOKEN_TTL: Duration = Duration::from_secs(crate::app::VERIFY_LINK_HOURS * 60 * 60);
+
+const MIN_PASSWORD_CHARS: usize = 12;
+// Argon2 will hash anything, however long; there's no need to let it
+const MAX_PASSWORD_CHARS: usize = 256;
+
+#[derive(Debug)]
+pub enum ResetError {
+    WeakPassword(String),
+    // Unknown, used or expired; the reset page doesn't say which
+    InvalidToken,
+    Storage(String),
+}
+
+impl ResetError {
+    // Shown on the reset page, so storage errors are left to the log
+    pub fn message(&self) -> String {
+        match self {
+            ResetError::WeakPassword(reason) => reason.clone(),
+            ResetError::InvalidToken => {
+                "This reset link has expired or has already been used. Please ask for a new one.".to_string()
+            }
+            ResetError::Storage(_) => "Your password couldn't be reset. Please try again later.".to_string(),
+        }
+    }
+}
+
+impl From<surrealdb::Error> for ResetError {
+    fn from(e: surrealdb::Error) -> Self {
+        ResetError::Storage(e.to_string())
+    }
+}
+
+fn reset_email(to: &str, token: &str) -> Email {
+    let settings = site_settings();
+    Email {
+        to: to.to_string(),
+        subject: format!("Reset your password for {}", settings.site_title),
+        body: format!(
+            "Someone, hopefully you, asked to reset the password for this account on {}.\n\n\
+             To choose a new one, open this link within {} minutes:\n\n{}/login/reset/{}\n\n\
+             It can only be used once. If you didn't ask for this, you can ignore this email; \
+             your password hasn't changed.\n",
+            settings.site_title,
+            RESET_TOKEN_TTL.as_secs() / 60,
+            site_url(),
+            token,
+        ),
+        reply_to: None,
+    }
+}
+
+// Emails a reset link if the address has an account. Whether it does isn't revealed: the
+// caller tells everyone the same thing, and since the work is done in the background, at the
+// same speed too.
+pub fn request_password_reset(db: &Surreal<Db>, email: &str) {
+    let (db, email) = (db.clone(), email.to_string());
+    tokio::spawn(async move {
+        if let Err(e) = send_reset_link(&db, &email).await {
+            log!("Failed to create a password reset: {}", e);
+        }
+    });
+}
+
+async fn send_reset_link(db: &Surreal<Db>, email: &str) -> Result<(), surrealdb::Error> {
+    let Some(user) = find_user_by_email(db, email).await? else {
+        log!("Password reset asked for an unknown account");
+        return Ok(());
+    };
+    let token = generate_token();
+    db.query("DELETE password_reset WHERE user = $user OR expires_at < time::now()")
+        .query(
+            "CREATE password_reset SET token_hash = $token_hash, user = $user, \
+             created_at = time::now(), expires_at = time::now() + $ttl",
+        )
+        .bind(("token_hash", hash_token(&token)))
+        .bind(("user", user.id.clone()))
+        .bind(("ttl", surrealdb::sql::Duration::from(RESET_TOKEN_TTL)))
+        .await?
+        .check()?;
+    queue_email(reset_email(&user.email, &token));
+    Ok(())
+}
+
+fn check_new_password(password: &str) -> Result<(), ResetError> {
+    let length = password.chars().count();
+    if length < MIN_PASSWORD_CHARS {
+        return Err(ResetError::WeakPassword(format!(
+            "Please choose a password of at least {} characters.",
+            MIN_PASSWORD_CHARS
+        )));
+    }
+    if length > MAX_PASSWORD_CHARS {
+        return Err(ResetError::WeakPassword(format!(
+            "Please choose a password of at most {} characters.",
+            MAX_PASSWORD_CHARS
+        )));
+    }
+    Ok(())
+}
+
+// A claimed reset or verification token
+#[derive(Debug, Deserialize)]
+struct UsedReset {
+    user: Thing,
+    expires_at: Datetime,
+}
+
+// The account a claimed token was for, if it was still good at `now`. A token that has
+// expired is still deleted when it's claimed, so it can't be tried again.
+fn unexpired_user(used: Option<UsedReset>, now: &Datetime) -> Result<Thing, ResetError> {
+    match used {
+        Some(used) if used.expires_at > *now => Ok(used.user),
+        _ => Err(ResetError::InvalidToken),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ResetUser {
+    email: String,
+}
+
+// Sets a new password with a token from a reset email, then signs the account out everywhere.
+pub async fn reset_password(db: &Surreal<Db>, token: &str, new_password: &str) -> Result<(), ResetError> {
+    // Checked first, so a password that's turned down doesn't use up the link
+    check_new_password(new_password)?;
+
+    // Hashing is slow on purpose, so it's only done for a link that works, and off the async
+    // runtime
+    let token_hash = hash_token(token);
+    let mut response = db
+        .query("SELECT VALUE id FROM password_reset WHERE token_hash = $token_hash AND expires_at > time::now() LIMIT 1")
+        .bind(("token_hash", token_hash.clone()))
+        .await?;
+    let found: Option<Thing> = response.take(0)?;
+    if found.is_none() {
+        return Err(ResetError::InvalidToken);
+    }
+    let new_password = new_password.to_string();
+    let password_hash = tokio::task::spawn_blocking(move || hash_password(&new_password))
+        .await
+        .map_err(|e| ResetError::Storage(e.to_string()))?
+        .map_err(|e| ResetError::Storage(e.to_string()))?;
+
+    // Deleting the token is what claims it, so two requests with the same link can't both
+    // get through
+    let mut response = db
+        .query("DELETE password_reset WHERE token_hash = $token_hash RETURN BEFORE")
+        .bind(("token_hash", token_hash))
+        .await?;
+    let used: Option<UsedReset> = response.take(0)?;
+    let user = unexpired_user(used, &Datetime::default())?;
+
+    let mut response = db
+        // Following the emailed link shows the address is theirs, too
+        .query(
+            "UPDATE $user SET password_hash = $password_hash, \
+             email_verified_at = email_verified_at ?? time::now() RETURN email",
+        )
+        .bind(("user", user.clone()))
+        .bind(("password_hash", password_hash))
+        .await?;
+    let updated: Option<ResetUser> = response.take(0)?;
+    let Some(updated) = updated else {
+        return Err(ResetError::InvalidToken);
+    };
+
+    delete_user_sessions(db, &user).await?;
+    db.query("DELETE password_reset WHERE user = $user")
+        .bind(("user", user))
+        .await?
+        .check()?;
+    // Whoever forgot their password may well have locked themselves out trying to guess it
+    clear_login_failures(db, &account_throttle_key(&updated.email)).await?;
+    Ok(())
+}
+
+// --- Email Verification ---
+fn verification_email(to: &str, token: &str) -> Email {
+    let settings = site_settings();
+    Email {
+        to: to.to_string(),
+        subject: format!("Confirm your email address for {}", settings.site_title),
+        body: format!(
+            "This address was given for an account on {}.\n\n\
+             To confirm it's yours, open this link within {} hours:\n\n{}/login/verify/{}\n\n\
+             If you don't know what this is about, you can ignore this email.\n",
+            settings.site_title,
+            VERIFY_TOKEN_TTL.as_secs() / 3600,
+            site_url(),
+            token,
+        ),
+        reply_to: None,
+    }
+}
+
+// Emails the account a link to confirm its address, replacing any it was sent before.
+pub async fn send_verification_email(db: &Surreal<Db>, user: &Thing, email: &str) -> Result<(), surrealdb::Error> {
+    let token = generate_token();
+    db.query("DELETE email_verification WHERE user = $user OR expires_at < time::now()")
+        .query(
+            "CREATE email_verification SET token_hash = $token_hash, user = $user, \
+             created_at = time::now(), expires_at = time::now() + $ttl",
+        )
+        .bind(("token_hash", hash_token(&token)))
+        .bind(("user", user.clone()))
+        .bind(("ttl", surrealdb::sql::Duration::from(VERIFY_TOKEN_TTL)))
+        .await?
+        .check()?;
+    queue_email(verification_email(email, &token));
+    Ok(())
+}
+
+// Marks the account's address as confirmed with a token from a verification email.
+pub async fn verify_email(db: &Surreal<Db>, token: &str) -> Result<(), ResetError> {
+    // Deleting the token is what claims it, as for password resets
+    let mut response = db
+        .query("DELETE email_verification WHERE token_hash = $token_hash RETURN BEFORE")
+        .bind(("token_hash", hash_token(token)))
+        .await?;
+    let used: Option<UsedReset> = response.take(0)?;
+    let user = unexpired_user(used, &Datetime::default())?;
+
+    let mut response = db
+        .query("UPDATE $user SET email_verified_at = email_verified_at ?? time::now() RETURN email")
+        .bind(("user", user))
+        .await?;
+    let updated: Option<ResetUser> = response.take(0)?;
+    if updated.is_none() {
+        return Err(ResetError::InvalidToken);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(rfc3339: &str) -> Datetime {
+        Datetime::try_from(rfc3339).unwrap()
+    }
+
+    fn claimed(expires_at: &str) -> Option<UsedReset> {
+        Some(UsedReset { user: Thing::from(("user", "admin")), expires_at: at(expires_at) })
+    }
+
+    #[test]
+    fn tokens_work_until_they_expire() {
+        let now = at("2026-01-01T12:00:00Z");
+        assert_eq!(unexpired_user(claimed("2026-01-01T12:30:00Z"), &now).unwrap(), Thing::from(("user", "admin")));
+        assert!(matches!(unexpired_user(claimed("2026-01-01T12:00:00Z"), &now), Err(ResetError::InvalidToken)));
+        assert!(matches!(unexpired_user(claimed("2026-01-01T11:00:00Z"), &now), Err(ResetError::InvalidToken)));
+        assert!(matches!(unexpired_user(None, &now), Err(ResetError::InvalidToken)));
+    }
+
+    #[test]
+    fn new_passwords_must_be_a_sensible_length() {
+        assert!(check_new_password(&"a".repeat(MIN_PASSWORD_CHARS)).is_ok());
+        assert!(matches!(check_new_password(&"a".repeat(MIN_PASSWORD_CHARS - 1)), Err(ResetError::WeakPassword(_))));
+        assert!(matches!(check_new_password(&"a".repeat(MAX_PASSWORD_CHARS + 1)), Err(ResetError::WeakPassword(_))));
+    }
+}
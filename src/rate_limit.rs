@@ -0,0 +1,231 @@
+// Per-IP request rate limiting, used as an axum middleware layer on the public API routes.
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use axum::body::Body;
+use axum::extract::{ConnectInfo, State};
+use axum::http::{header, HeaderMap, HeaderValue, Request, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use leptos::logging::log;
+
+// Drop idle buckets once the table gets this big, so it can't grow without bound.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+// --- Client Address ---
+// A range of addresses in CIDR notation, e.g. `172.16.0.0/12`; a bare address is a range of one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    address: IpAddr,
+    prefix: u8,
+}
+
+impl IpNetwork {
+    pub fn parse(text: &str) -> Option<IpNetwork> {
+        let (address, prefix) = match text.trim().split_once('/') {
+            Some((address, prefix)) => (address.parse::<IpAddr>().ok()?, Some(prefix.parse::<u8>().ok()?)),
+            None => (text.trim().parse::<IpAddr>().ok()?, None),
+        };
+        let max_prefix = if address.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max_prefix);
+        (prefix <= max_prefix).then_some(IpNetwork { address, prefix })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        // IPv4 peers on a dual-stack socket show up as `::ffff:a.b.c.d`
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            ip => ip,
+        };
+        match (self.address, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+fn trusted_proxies() -> &'static [IpNetwork] {
+    static TRUSTED: OnceLock<Vec<IpNetwork>> = OnceLock::new();
+    TRUSTED.get_or_init(crate::config::trusted_proxies)
+}
+
+// The client's address. Connections from a trusted proxy (see `crate::config::trusted_proxies`)
+// are taken to be for the rightmost address in `X-Forwarded-For` that isn't a trusted proxy
+// too: entries further left were written by the client, who can put anything there.
+pub fn client_ip(headers: &HeaderMap, peer: SocketAddr) -> IpAddr {
+    forwarded_client_ip(headers, peer.ip(), trusted_proxies())
+}
+
+fn forwarded_client_ip(headers: &HeaderMap, peer: IpAddr, trusted: &[IpNetwork]) -> IpAddr {
+    let is_trusted = |ip: IpAddr| trusted.iter().any(|network| network.contains(ip));
+    let mut client = peer;
+    if !is_trusted(client) {
+        return client;
+    }
+    let forwarded = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>()
+        .join(",");
+    for entry in forwarded.rsplit(',') {
+        // Anything unreadable could have come from anyone, so the last good hop is used
+        let Ok(ip) = entry.trim().parse::<IpAddr>() else {
+            break;
+        };
+        client = ip;
+        if !is_trusted(ip) {
+            break;
+        }
+    }
+    client
+}
+
+// --- Token Bucket ---
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+// Each client may make `burst` requests at once, refilled at `per_minute` requests per minute.
+pub struct RateLimiter {
+    burst: f64,
+    refill_per_sec: f64,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(burst: u32, per_minute: u32) -> Arc<Self> {
+        Arc::new(Self {
+            burst: burst as f64,
+            refill_per_sec: per_minute as f64 / 60.0,
+            buckets: Mutex::new(HashMap::new()),
+        })
+    }
+
+    // Takes a token for the client, or returns how long until one is available.
+    pub fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS {
+            let full_after = Duration::from_secs_f64(self.burst / self.refill_per_sec);
+            buckets.retain(|_, bucket| now.duration_since(bucket.updated_at) < full_after);
+        }
+
+        let bucket = buckets.entry(ip).or_insert(Bucket {
+            tokens: self.burst,
+            updated_at: now,
+        });
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.burst);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.refill_per_sec))
+        }
+    }
+}
+
+// --- Middleware ---
+// Layer it on a route with `middleware::from_fn_with_state(limiter, rate_limit_middleware)`.
+pub async fn rate_limit_middleware(
+    State(limiter): State<Arc<RateLimiter>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let ip = client_ip(request.headers(), peer);
+
+    match limiter.check(ip) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            log!("Rate limited request from {} to {}", ip, request.uri().path());
+            let seconds = retry_after.as_secs().max(1);
+            let mut response = StatusCode::TOO_MANY_REQUESTS.into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forwarded_for(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    fn ip(text: &str) -> IpAddr {
+        text.parse().unwrap()
+    }
+
+    fn networks(texts: &[&str]) -> Vec<IpNetwork> {
+        texts.iter().map(|text| IpNetwork::parse(text).unwrap()).collect()
+    }
+
+    #[test]
+    fn networks_match_their_prefix() {
+        let docker = IpNetwork::parse("172.16.0.0/12").unwrap();
+        assert!(docker.contains(ip("172.17.0.1")));
+        assert!(docker.contains(ip("::ffff:172.17.0.1")));
+        assert!(!docker.contains(ip("172.32.0.1")));
+        assert!(IpNetwork::parse("::1").unwrap().contains(ip("::1")));
+        assert!(IpNetwork::parse("0.0.0.0/0").unwrap().contains(ip("203.0.113.9")));
+        assert_eq!(IpNetwork::parse("10.0.0.0/33"), None);
+        assert_eq!(IpNetwork::parse("not an address"), None);
+    }
+
+    #[test]
+    fn untrusted_peers_are_taken_at_their_word() {
+        let trusted = networks(&["127.0.0.0/8"]);
+        let headers = forwarded_for("198.51.100.7");
+        assert_eq!(forwarded_client_ip(&headers, ip("203.0.113.9"), &trusted), ip("203.0.113.9"));
+    }
+
+    #[test]
+    fn trusted_proxies_pass_on_the_address_they_saw() {
+        let trusted = networks(&["127.0.0.0/8", "172.16.0.0/12"]);
+        let headers = forwarded_for("198.51.100.7");
+        assert_eq!(forwarded_client_ip(&headers, ip("172.17.0.1"), &trusted), ip("198.51.100.7"));
+        assert_eq!(forwarded_client_ip(&HeaderMap::new(), ip("172.17.0.1"), &trusted), ip("172.17.0.1"));
+    }
+
+    #[test]
+    fn spoofed_entries_left_of_the_proxy_are_ignored() {
+        let trusted = networks(&["172.16.0.0/12", "10.0.0.0/8"]);
+        // The client claimed 1.2.3.4; the outer proxy at 10.0.0.2 saw 198.51.100.7
+        let headers = forwarded_for("1.2.3.4, 198.51.100.7, 10.0.0.2");
+        assert_eq!(forwarded_client_ip(&headers, ip("172.17.0.1"), &trusted), ip("198.51.100.7"));
+        let headers = forwarded_for("1.2.3.4, garbage, 10.0.0.2");
+        assert_eq!(forwarded_client_ip(&headers, ip("172.17.0.1"), &trusted), ip("10.0.0.2"));
+    }
+
+    #[test]
+    fn buckets_refuse_past_the_burst() {
+        let limiter = RateLimiter::new(2, 1);
+        let client = ip("198.51.100.7");
+        assert!(limiter.check(client).is_ok());
+        assert!(limiter.check(client).is_ok());
+        assert!(limiter.check(client).is_err());
+        // Other clients have buckets of their own
+        assert!(limiter.check(ip("198.51.100.8")).is_ok());
+    }
+}
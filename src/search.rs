@@ -0,0 +1,95 @@
+// Full-text search over posts and projects. The `content_search` analyzer and the `*_search`
+// indexes are defined in schema.surql; SurrealDB ranks matches with BM25 and marks the matched
+// terms with `search::highlight`, and the marked text is turned into short HTML snippets here.
+use crate::markdown::plain_text;
+
+// Put around matched terms by `search::highlight`. Private-use characters don't turn up in
+// real writing, and they survive `plain_text`, unlike HTML tags.
+pub const MATCH_START: char = '\u{E000}';
+pub const MATCH_END: char = '\u{E001}';
+
+// How much of the text a snippet shows, and how much of that comes before the first match.
+const SNIPPET_CHARS: usize = 200;
+const SNIPPET_LEAD: usize = 60;
+
+fn push_escaped(html: &mut String, c: char) {
+    match c {
+        '&' => html.push_str("&amp;"),
+        '<' => html.push_str("&lt;"),
+        '>' => html.push_str("&gt;"),
+        '"' => html.push_str("&quot;"),
+        '\'' => html.push_str("&#39;"),
+        c => html.push(c),
+    }
+}
+
+// Escapes the marked text for HTML, wrapping each match in `<mark>`.
+fn marked_html(chars: &[char], mut inside: bool) -> String {
+    let mut html = String::new();
+    if inside {
+        html.push_str("<mark>");
+    }
+    for &c in chars {
+        match c {
+            MATCH_START if !inside => {
+                html.push_str("<mark>");
+                inside = true;
+            }
+            MATCH_END if inside => {
+                html.push_str("</mark>");
+                inside = false;
+            }
+            MATCH_START | MATCH_END => {}
+            c => push_escaped(&mut html, c),
+        }
+    }
+    if inside {
+        html.push_str("</mark>");
+    }
+    html
+}
+
+// A short plain-text field such as a title, as HTML.
+pub fn highlight_html(marked: &str) -> String {
+    let chars: Vec<char> = marked.chars().collect();
+    marked_html(&chars, false)
+}
+
+// True if `search::highlight` found a match in the text.
+pub fn has_match(marked: &str) -> bool {
+    marked.contains(MATCH_START)
+}
+
+// A snippet of marked-up Markdown as HTML: a couple of lines of its text, from just
+// before the first match and cut at word boundaries.
+pub fn snippet_html(marked_markdown: &str) -> String {
+    let chars: Vec<char> = plain_text(marked_markdown).chars().collect();
+    let first_match = chars.iter().position(|&c| c == MATCH_START).unwrap_or(0);
+
+    let mut start = first_match.saturating_sub(SNIPPET_LEAD);
+    if start > 0 {
+        if let Some(space) = chars[start..first_match].iter().position(|c| c.is_whitespace()) {
+            start += space + 1;
+        }
+    }
+    let mut end = (start + SNIPPET_CHARS).min(chars.len());
+    if end < chars.len() {
+        if let Some(space) = chars[start..end].iter().rposition(|c| c.is_whitespace()) {
+            end = start + space;
+        }
+    }
+
+    // A snippet can start in the middle of a match
+    let opened = chars[..start].iter().filter(|&&c| c == MATCH_START).count();
+    let closed = chars[..start].iter().filter(|&&c| c == MATCH_END).count();
+
+    let mut html = String::new();
+    if start > 0 {
+        html.push('…');
+    }
+    html.push_str(&marked_html(&chars[start..end], opened > closed));
+    if end < chars.len() {
+        html.push('…');
+    }
+    html
+}
@@ -0,0 +1,156 @@
+// Site-wide settings edited in the admin panel: the site title, tagline, owner's name, and the
+// email address and profile links shown on the contacts page. They live in the
+// `site_settings:main` record.
+//
+// Every page needs them before anything else has loaded, so the server keeps a copy in memory
+// and `shell` embeds it in the page as JSON, which the browser reads back on hydration, much
+// like the CSRF token in `crate::csrf`.
+use serde::{Deserialize, Serialize};
+
+pub const SETTINGS_ELEMENT_ID: &str = "site-settings";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProfileLink {
+    pub label: String,
+    pub url: String,
+}
+
+impl ProfileLink {
+    // The address without its scheme, as shown on the contacts page
+    pub fn display_url(&self) -> &str {
+        let url = self.url.trim_start_matches("https://").trim_start_matches("http://");
+        url.trim_start_matches("www.").trim_end_matches('/')
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SiteSettings {
+    pub site_title: String,
+    pub tagline: String,
+    pub owner_name: String,
+    pub email: String,
+    // Left out of the request altogether when empty, since server functions send forms
+    #[serde(default)]
+    pub profile_links: Vec<ProfileLink>,
+}
+
+// What the site said before any of this was editable
+impl Default for SiteSettings {
+    fn default() -> Self {
+        SiteSettings {
+            site_title: "Shiloh Antony John".to_string(),
+            tagline: "A Stoic programmer who aspires to be an entrepreneur to do good to humanity.".to_string(),
+            owner_name: "Shiloh Antony John".to_string(),
+            email: "shilohantonyjohn@email.com".to_string(),
+            profile_links: vec![
+                ProfileLink { label: "X".to_string(), url: "https://x.com/ShilohAJohn".to_string() },
+                ProfileLink { label: "GitHub".to_string(), url: "https://github.com/ShilohAntonyJohn".to_string() },
+            ],
+        }
+    }
+}
+
+impl SiteSettings {
+    // The X account among the profile links, as `@handle`, for Twitter cards.
+    pub fn x_handle(&self) -> Option<String> {
+        self.profile_links.iter().find_map(|link| {
+            let url = link.url.trim_start_matches("https://").trim_start_matches("http://");
+            let url = url.trim_start_matches("www.");
+            let handle = url.strip_prefix("x.com/").or_else(|| url.strip_prefix("twitter.com/"))?;
+            let handle = handle.split(['/', '?', '#']).next().unwrap_or_default();
+            (!handle.is_empty()).then(|| format!("@{}", handle))
+        })
+    }
+
+    // Checks what the admin panel sent, tidying it up on the way.
+    pub fn validate(self) -> Result<SiteSettings, String> {
+        let site_title = self.site_title.trim().to_string();
+        let owner_name = self.owner_name.trim().to_string();
+        let email = self.email.trim().to_string();
+        if site_title.is_empty() || owner_name.is_empty() {
+            return Err("The site title and owner name can't be empty.".to_string());
+        }
+        if !email.is_empty() && (!email.contains('@') || email.contains(char::is_whitespace)) {
+            return Err(format!("\"{}\" isn't an email address.", email));
+        }
+        let mut profile_links = Vec::new();
+        for link in self.profile_links {
+            let (label, url) = (link.label.trim().to_string(), link.url.trim().to_string());
+            if label.is_empty() && url.is_empty() {
+                continue;
+            }
+            if label.is_empty() || !(url.starts_with("https://") || url.starts_with("http://")) {
+                return Err(format!("Profile links need a label and an http(s) address, not \"{} {}\".", label, url));
+            }
+            profile_links.push(ProfileLink { label, url });
+        }
+        Ok(SiteSettings {
+            site_title,
+            tagline: self.tagline.trim().to_string(),
+            owner_name,
+            email,
+            profile_links,
+        })
+    }
+
+    // For embedding in a `<script type="application/json">`, which must not contain `</`.
+    pub fn to_embedded_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default().replace("</", "<\\/")
+    }
+}
+
+// The current settings: the server's copy, or what `shell` embedded in the page.
+pub fn site_settings() -> SiteSettings {
+    #[cfg(feature = "ssr")]
+    {
+        store::current()
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        leptos::prelude::document()
+            .get_element_by_id(SETTINGS_ELEMENT_ID)
+            .and_then(|element| element.text_content())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub use store::{load_site_settings, save_site_settings};
+
+#[cfg(feature = "ssr")]
+mod store {
+    use std::sync::{OnceLock, RwLock};
+
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    use super::SiteSettings;
+
+    fn cache() -> &'static RwLock<SiteSettings> {
+        static CACHE: OnceLock<RwLock<SiteSettings>> = OnceLock::new();
+        CACHE.get_or_init(|| RwLock::new(SiteSettings::default()))
+    }
+
+    pub fn current() -> SiteSettings {
+        cache().read().map(|settings| settings.clone()).unwrap_or_default()
+    }
+
+    // Reads the settings into memory. Called once on start-up; until the admin panel saves
+    // some, the defaults are used.
+    pub async fn load_site_settings(db: &Surreal<Db>) -> Result<(), surrealdb::Error> {
+        let stored: Option<SiteSettings> = db.select(("site_settings", "main")).await?;
+        if let (Some(stored), Ok(mut settings)) = (stored, cache().write()) {
+            *settings = stored;
+        }
+        Ok(())
+    }
+
+    pub async fn save_site_settings(db: &Surreal<Db>, new_settings: SiteSettings) -> Result<(), surrealdb::Error> {
+        let _saved: Option<SiteSettings> = db.upsert(("site_settings", "main")).content(new_settings.clone()).await?;
+        if let Ok(mut settings) = cache().write() {
+            *settings = new_settings;
+        }
+        Ok(())
+    }
+}
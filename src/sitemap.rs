@@ -0,0 +1,108 @@
+// What crawlers are given: `/sitemap.xml`, listing the fixed pages and every publicly visible
+// post and project, and `/robots.txt`, which keeps them out of the admin side and points them
+// at the sitemap.
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use leptos::logging::log;
+use serde::Deserialize;
+use surrealdb::engine::local::Db;
+use surrealdb::Surreal;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::app::PUBLICLY_VISIBLE;
+use crate::config::{robots_disallow, site_url};
+use crate::feed::cached_response;
+
+#[derive(Debug, Deserialize)]
+struct SitemapRecord {
+    slug: String,
+    #[serde(with = "time::serde::rfc3339")]
+    updated_at: OffsetDateTime,
+}
+
+struct SitemapUrl {
+    path: String,
+    lastmod: Option<OffsetDateTime>,
+}
+
+async fn visible_records(db: &Surreal<Db>, table: &str) -> Result<Vec<SitemapRecord>, surrealdb::Error> {
+    let mut response = db
+        .query(format!(
+            "SELECT slug, created_at, updated_at FROM type::table($table) WHERE {PUBLICLY_VISIBLE} AND slug != NONE ORDER BY created_at DESC"
+        ))
+        .bind(("table", table.to_string()))
+        .await?;
+    response.take(0)
+}
+
+fn lastmod(records: &[SitemapRecord]) -> Option<OffsetDateTime> {
+    records.iter().map(|record| record.updated_at).max()
+}
+
+fn render_sitemap(urls: &[SitemapUrl]) -> String {
+    let base = site_url();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for url in urls {
+        xml.push_str("<url>\n");
+        // Slugs are URL-safe, so nothing here needs escaping
+        xml.push_str(&format!("<loc>{}{}</loc>\n", base, url.path));
+        if let Some(lastmod) = url.lastmod {
+            let lastmod = lastmod.replace_nanosecond(0).unwrap_or(lastmod);
+            xml.push_str(&format!("<lastmod>{}</lastmod>\n", lastmod.format(&Rfc3339).unwrap_or_default()));
+        }
+        xml.push_str("</url>\n");
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+pub async fn serve_sitemap(db: &Surreal<Db>, headers: &HeaderMap) -> Response {
+    let (posts, projects) = match (
+        visible_records(db, "blog_post").await,
+        visible_records(db, "project").await,
+    ) {
+        (Ok(posts), Ok(projects)) => (posts, projects),
+        (Err(e), _) | (_, Err(e)) => {
+            log!("Failed to read sitemap records from SurrealDB: {:?}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    // The listing pages change whenever something listed on them does
+    let views_lastmod = lastmod(&posts);
+    let projects_lastmod = lastmod(&projects);
+    let home_lastmod = views_lastmod.max(projects_lastmod);
+
+    let mut urls = vec![
+        SitemapUrl { path: "/".to_string(), lastmod: home_lastmod },
+        SitemapUrl { path: "/projects".to_string(), lastmod: projects_lastmod },
+        SitemapUrl { path: "/views".to_string(), lastmod: views_lastmod },
+        SitemapUrl { path: "/contacts".to_string(), lastmod: None },
+    ];
+    urls.extend(projects.iter().map(|project| SitemapUrl {
+        path: format!("/projects/{}", project.slug),
+        lastmod: Some(project.updated_at),
+    }));
+    urls.extend(posts.iter().map(|post| SitemapUrl {
+        path: format!("/views/{}", post.slug),
+        lastmod: Some(post.updated_at),
+    }));
+
+    cached_response(
+        headers,
+        "application/xml; charset=utf-8",
+        render_sitemap(&urls),
+        home_lastmod.unwrap_or(OffsetDateTime::UNIX_EPOCH),
+    )
+}
+
+pub fn robots_txt() -> impl IntoResponse {
+    let mut body = String::from("User-agent: *\n");
+    for path in robots_disallow() {
+        body.push_str(&format!("Disallow: {}\n", path));
+    }
+    body.push_str(&format!("\nSitemap: {}/sitemap.xml\n", site_url()));
+    ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], body)
+}
@@ -0,0 +1,121 @@
+// URL slugs for posts and projects. A slug is made from the title once, when the
+// record is created, and never changes afterwards so links to it keep working.
+use serde::Deserialize;
+use surrealdb::engine::local::Db;
+use surrealdb::sql::Thing;
+use surrealdb::Surreal;
+
+const MAX_SLUG_LEN: usize = 80;
+
+pub fn slugify(title: &str) -> String {
+    let slug = ::slug::slugify(title);
+    let slug = match slug.char_indices().nth(MAX_SLUG_LEN) {
+        Some((end, _)) => slug[..end].trim_end_matches('-').to_string(),
+        None => slug,
+    };
+    if slug.is_empty() {
+        "untitled".to_string()
+    } else {
+        slug
+    }
+}
+
+async fn slug_taken(db: &Surreal<Db>, table: &str, slug: &str) -> Result<bool, surrealdb::Error> {
+    let mut response = db
+        .query("SELECT VALUE id FROM type::table($table) WHERE slug = $slug LIMIT 1")
+        .bind(("table", table.to_string()))
+        .bind(("slug", slug.to_string()))
+        .await?;
+    let ids: Vec<Thing> = response.take(0)?;
+    Ok(!ids.is_empty())
+}
+
+// A slug for the title that no other record in the table uses, adding `-2`, `-3`, ... if needed.
+pub async fn unique_slug(db: &Surreal<Db>, table: &str, title: &str) -> Result<String, surrealdb::Error> {
+    let base = slugify(title);
+    let mut slug = base.clone();
+    let mut n = 2;
+    while slug_taken(db, table, &slug).await? {
+        slug = format!("{}-{}", base, n);
+        n += 1;
+    }
+    Ok(slug)
+}
+
+// Gives a slug to every record created before slugs existed, then makes sure the unique index
+// on `slug` is in place. The index is defined here rather than in schema.surql, which runs
+// first: an index that is built while records are still missing their slugs would have every
+// one of those writes checked against it. Databases that already have the index get it back
+// once the backfill is done.
+pub async fn backfill_slugs(db: &Surreal<Db>, table: &str) -> Result<(), surrealdb::Error> {
+    #[derive(Deserialize)]
+    struct Unslugged {
+        id: Thing,
+        title: String,
+    }
+
+    // Only ever "blog_post" or "project"; index statements can't take the table as a parameter
+    let index = format!("{table}_slug");
+
+    let mut response = db
+        .query("SELECT id, title FROM type::table($table) WHERE slug IS NONE")
+        .bind(("table", table.to_string()))
+        .await?;
+    let records: Vec<Unslugged> = response.take(0)?;
+
+    if !records.is_empty() {
+        db.query(format!("REMOVE INDEX IF EXISTS {index} ON TABLE {table}"))
+            .await?
+            .check()?;
+    }
+    for record in records {
+        let slug = unique_slug(db, table, &record.title).await?;
+        db.query("UPDATE $record SET slug = $slug")
+            .bind(("record", record.id))
+            .bind(("slug", slug))
+            .await?
+            .check()?;
+    }
+
+    // Fails, and so stops start-up, if two records somehow share a slug
+    db.query(format!("DEFINE INDEX IF NOT EXISTS {index} ON TABLE {table} COLUMNS slug UNIQUE"))
+        .await?
+        .check()?;
+    let mut response = db
+        .query("SELECT VALUE id FROM type::table($table) WHERE slug IS NONE OR slug = '' LIMIT 1")
+        .bind(("table", table.to_string()))
+        .await?;
+    let missing: Vec<Thing> = response.take(0)?;
+    if let Some(id) = missing.first() {
+        // Not a database error as such, but it stops start-up the same way
+        return Err(surrealdb::Error::Api(surrealdb::error::Api::Query(format!(
+            "{} still has no slug after the backfill",
+            id
+        ))));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugs_are_lowercase_words_joined_by_dashes() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("  Rust & Leptos  "), "rust-leptos");
+    }
+
+    #[test]
+    fn titles_without_letters_or_digits_still_get_a_slug() {
+        assert_eq!(slugify("!!!"), "untitled");
+        assert_eq!(slugify(""), "untitled");
+    }
+
+    #[test]
+    fn long_slugs_are_cut_without_a_trailing_dash() {
+        let slug = slugify(&"word ".repeat(40));
+        assert!(slug.len() <= MAX_SLUG_LEN);
+        assert!(!slug.ends_with('-'));
+    }
+}
@@ -0,0 +1,69 @@
+// Tags shared by posts and projects. Each tag is a `tag` record keyed by its slug, and a
+// post or project links to its tags with `tagged` edges: `blog_post:x->tagged->tag:rust`.
+use surrealdb::engine::local::Db;
+use surrealdb::{RecordId, Surreal};
+
+use crate::app::Tag;
+use crate::slug::slugify;
+
+// Tags as the admin typed them, tidied up and with duplicates dropped.
+pub fn normalize_tags(names: &[String]) -> Vec<Tag> {
+    let mut tags: Vec<Tag> = Vec::new();
+    for name in names {
+        let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        if name.is_empty() {
+            continue;
+        }
+        let slug = slugify(&name);
+        if !tags.iter().any(|tag| tag.slug == slug) {
+            tags.push(Tag { name, slug });
+        }
+    }
+    tags
+}
+
+// Replaces the record's tags, creating tags that don't exist yet and dropping ones nothing uses.
+pub async fn set_tags(db: &Surreal<Db>, record: RecordId, names: &[String]) -> Result<(), surrealdb::Error> {
+    let tags = normalize_tags(names);
+    db.query(
+        "DELETE $record->tagged; \
+         FOR $tag IN $tags { \
+             LET $target = type::thing('tag', $tag.slug); \
+             UPSERT $target SET name = $tag.name, slug = $tag.slug; \
+             RELATE $record->tagged->$target; \
+         }; \
+         DELETE tag WHERE count(<-tagged) = 0;",
+    )
+    .bind(("record", record))
+    .bind(("tags", tags))
+    .await?
+    .check()?;
+    Ok(())
+}
+
+pub async fn find_tag(db: &Surreal<Db>, slug: &str) -> Result<Option<Tag>, surrealdb::Error> {
+    let tag: Option<Tag> = db.select(("tag", slug)).await?;
+    Ok(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn tags_are_tidied_and_deduplicated_by_slug() {
+        let tags = normalize_tags(&names(&["  Web   Dev ", "rust", "", "web dev", "Rust"]));
+        let pairs: Vec<(&str, &str)> = tags.iter().map(|tag| (tag.name.as_str(), tag.slug.as_str())).collect();
+        assert_eq!(pairs, [("Web Dev", "web-dev"), ("rust", "rust")]);
+    }
+
+    #[test]
+    fn no_tags_is_fine() {
+        assert!(normalize_tags(&[]).is_empty());
+        assert!(normalize_tags(&names(&["   "])).is_empty());
+    }
+}